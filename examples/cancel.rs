@@ -1,7 +1,7 @@
 use std::time;
 use context_async::{Context, Timer};
 
-/// In this example, we spawn a few context from a root context, and show how `cancel` works.
+// In this example, we spawn a few context from a root context, and show how `cancel` works.
 
 async fn a_very_heavy_task() {
     tokio::time::sleep(tokio::time::Duration::from_secs(100)).await;
@@ -4,11 +4,11 @@ use std::time::Duration;
 use tokio::sync::RwLock;
 use context_async::{Context, Timer, With};
 
-/// In this lib, our Timer doesn't store any data.
-/// However, we usually need to share data with our context.
-///
-/// In this example, we provide a simple extension implementation.
-/// We shore a `User` in our context.
+// Our Timer can carry typed values, see `Timer::with_value`.
+// However, sometimes we want our own context type with typed fields.
+//
+// In this example, we provide a simple extension implementation.
+// We shore a `User` in our context.
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -0,0 +1,203 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time;
+use actix_web::http::header::HeaderName;
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpMessage, ResponseError};
+use crate::{CancelGuard, Error, Timer};
+use crate::header::{self, REQUEST_TIMEOUT};
+
+/// The configuration of the [`Timer`] extractor of actix-web.
+///
+/// Register it with `App::app_data`, either directly or wrapped in `web::Data`.
+/// Without configuration, the timeout is read from the `X-Request-Timeout` header,
+/// and there is no default timeout. The statuses of the error responses are used by
+/// [`crate::TimerMiddleware`] for the [`Error`]s of the request.
+///
+/// # Examples
+/// ```rust
+/// use std::time;
+/// use actix_web::http::header::HeaderName;
+/// use actix_web::{web, App};
+/// use context_async::{Timer, TimerConfig};
+///
+/// async fn index(ctx: Timer) -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = App::new()
+///     .app_data(web::Data::new(
+///         TimerConfig::new()
+///             .header(HeaderName::from_static("grpc-timeout"))
+///             .default_timeout(time::Duration::from_secs(5))
+///     ))
+///     .route("/", web::get().to(index));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimerConfig {
+    header: HeaderName,
+    default_timeout: Option<time::Duration>,
+    root: Option<Timer>,
+    timeout_status: StatusCode,
+    cancelled_status: StatusCode,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimerConfig {
+    /// create a configuration, which reads the timeout of a request from the `X-Request-Timeout`
+    /// header, without default timeout.
+    pub fn new() -> Self {
+        Self {
+            header: HeaderName::from_static(REQUEST_TIMEOUT),
+            default_timeout: None,
+            root: None,
+            timeout_status: StatusCode::GATEWAY_TIMEOUT,
+            cancelled_status: StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// set the header carrying the timeout of a request.
+    ///
+    /// Its value is either a plain number of milliseconds, or a `grpc-timeout` value such as `100m`.
+    pub fn header(mut self, header: HeaderName) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// set the timeout of a request without a valid timeout header.
+    pub fn default_timeout(mut self, timeout: time::Duration) -> Self {
+        self.default_timeout = Some(timeout);
+        self
+    }
+
+    /// spawn every request context from `root`, so that cancelling `root`
+    /// cancels every in-flight request.
+    pub fn root(mut self, root: Timer) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    /// set the status of the response when the request context is timeout,
+    /// `504 Gateway Timeout` by default.
+    pub fn timeout_status(mut self, status: StatusCode) -> Self {
+        self.timeout_status = status;
+        self
+    }
+
+    /// set the status of the response when the request context is cancelled,
+    /// `503 Service Unavailable` by default, or such as `499 Client Closed Request`.
+    pub fn cancelled_status(mut self, status: StatusCode) -> Self {
+        self.cancelled_status = status;
+        self
+    }
+
+    /// return the status of the response for `err`.
+    pub(crate) fn status(&self, err: &Error) -> StatusCode {
+        if err.is_timeout() {
+            self.timeout_status
+        } else {
+            self.cancelled_status
+        }
+    }
+
+    /// return the configuration registered for `req`, or the default one.
+    #[cfg(feature = "actix-web-middleware")]
+    pub(crate) fn of(req: &actix_web::HttpRequest) -> Self {
+        Self::from_req(req).cloned().unwrap_or_default()
+    }
+
+    fn from_req(req: &actix_web::HttpRequest) -> Option<&Self> {
+        req.app_data::<Self>()
+            .or_else(|| req.app_data::<web::Data<Self>>().map(|data| data.get_ref()))
+    }
+
+    /// create the context of `req`.
+    pub(crate) fn timer(&self, req: &actix_web::HttpRequest) -> Timer {
+        let header_value = req.headers().get(&self.header)
+            .and_then(|value| value.to_str().ok());
+
+        header::request_timer(header_value, self.default_timeout, self.root.as_ref())
+    }
+}
+
+/// The context of a request, stored in the request extensions.
+///
+/// The request extensions are cleared when the request is dropped, that is, when
+/// the response is sent or the client disconnects: the guard then cancels the context.
+struct RequestTimer {
+    timer: Timer,
+    _guard: CancelGuard,
+    // the context replaced by a scoped one, see `scope_request_timer`.
+    _parent: Option<Box<RequestTimer>>,
+}
+
+/// return the context of `req`, create it on first use.
+pub(crate) fn request_timer(req: &actix_web::HttpRequest) -> Timer {
+    if let Some(request_timer) = req.extensions().get::<RequestTimer>() {
+        return request_timer.timer.clone();
+    }
+
+    let timer = match TimerConfig::from_req(req) {
+        Some(config) => config.timer(req),
+        None => TimerConfig::new().timer(req),
+    };
+
+    req.extensions_mut().insert(RequestTimer {
+        timer: timer.clone(),
+        _guard: timer.cancel_guard(),
+        _parent: None,
+    });
+
+    timer
+}
+
+/// replace the context of `req` by a child, whose deadline is shortened to `timeout`.
+#[cfg(feature = "actix-web-middleware")]
+pub(crate) fn scope_request_timer(req: &actix_web::HttpRequest, timeout: Option<time::Duration>) -> Timer {
+    let parent = request_timer(req);
+    let timer = parent.spawn_child(timeout);
+
+    let mut extensions = req.extensions_mut();
+    let parent = extensions.remove::<RequestTimer>().map(Box::new);
+    extensions.insert(RequestTimer {
+        timer: timer.clone(),
+        _guard: timer.cancel_guard(),
+        _parent: parent,
+    });
+
+    timer
+}
+
+/// Extract the context of the request.
+///
+/// Every extraction in the same request returns the same [`Timer`], see [`TimerConfig`].
+/// The context is cancelled once the request is dropped: after the response is sent,
+/// or when the client disconnects.
+impl actix_web::FromRequest for Timer {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &actix_web::HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        let timer = request_timer(req);
+
+        Box::pin(async {
+            Ok(timer)
+        })
+    }
+}
+
+/// Turn an [`Error`] into a response: `504 Gateway Timeout` for a timeout,
+/// or `503 Service Unavailable` for a cancellation.
+///
+/// A response has no access to its request, so the statuses of [`TimerConfig`] are applied
+/// by [`crate::TimerMiddleware`], which maps the [`Error`] responses of the service it wraps.
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        TimerConfig::new().status(self)
+    }
+}
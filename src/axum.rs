@@ -0,0 +1,46 @@
+use std::convert::Infallible;
+use axum_core::extract::FromRequestParts;
+use http::request::Parts;
+use crate::Timer;
+use crate::header::{request_timer, REQUEST_TIMEOUT};
+
+/// Extract the context of the request.
+///
+/// It returns the [`Timer`] installed by a layer such as `ContextLayer`, if any.
+/// Otherwise, it builds one from the `X-Request-Timeout` header, a plain number
+/// of milliseconds or a `grpc-timeout` value, and installs it in the request
+/// extensions, so that every extraction in the same request returns the same [`Timer`].
+///
+/// The built context is only ended by its deadline: the request is dropped as soon as
+/// the handler returns, before a streaming body is sent, so it cannot cancel the context.
+/// The header, a default timeout, a root context and cancelling on client disconnect
+/// are configured by installing `ContextLayer` instead, with the `tower` feature.
+///
+/// # Examples
+/// ```rust
+/// use axum::{routing::get, Router};
+/// use context_async::{Context, Timer};
+///
+/// async fn index(ctx: Timer) -> String {
+///     format!("{:?}", ctx.deadline().await)
+/// }
+///
+/// let app: Router = Router::new().route("/", get(index));
+/// ```
+impl<S: Send + Sync> FromRequestParts<S> for Timer {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        if let Some(timer) = parts.extensions.get::<Timer>() {
+            return Ok(timer.clone());
+        }
+
+        let header_value = parts.headers.get(REQUEST_TIMEOUT)
+            .and_then(|value| value.to_str().ok());
+        let timer = request_timer(header_value, None, None);
+
+        parts.extensions.insert(timer.clone());
+
+        Ok(timer)
+    }
+}
@@ -1,8 +1,9 @@
 use std::future::Future;
+use std::sync::Arc;
 use std::time;
 #[cfg(feature = "name")]
 use crate::name::Name;
-use crate::{Error, Timer};
+use crate::{Done, Error, Key, TaskHandle, Timer};
 
 /// The [`Context`] trait defines the required methods for `Context`.
 /// It can define a duration, be cancellable, and immediately cancel
@@ -43,7 +44,7 @@ pub trait Context: Clone + Send + Sync {
     /// return the basic [`Timer`].
     fn timer(&self) -> Timer;
 
-    /// return the name of this context
+    /// return the name of this context, a path below the name of its parent.
     #[cfg(feature = "name")]
     async fn name(&self) -> Name {
         self.timer().name().await
@@ -60,6 +61,31 @@ pub trait Context: Clone + Send + Sync {
         self.timer().cancel().await
     }
 
+    /// cancel this context with a cause, then cancel all its childs with the same cause.
+    ///
+    /// The cause is returned as [`Error::ContextCancelledWith`] by [`Self::error`] and [`Self::handle`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use context_async::{Context, Error, Timer};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ctx = Timer::background();
+    /// let child = ctx.spawn().await;
+    ///
+    /// let cause = Arc::new(std::io::Error::other("shutdown"));
+    /// ctx.cancel_with_reason(cause.clone()).await;
+    ///
+    /// let err = child.error().await.unwrap();
+    /// assert!(err.is_cancelled());
+    /// assert_eq!(err.to_string(), "context cancelled: shutdown");
+    /// # });
+    /// ```
+    async fn cancel_with_reason(&self, reason: Arc<dyn std::error::Error + Send + Sync>) {
+        self.timer().cancel_with_reason(reason).await
+    }
+
     /// check whether this context is cancelled or not.
     async fn is_cancelled(&self) -> bool {
         self.timer().is_cancelled().await
@@ -71,14 +97,93 @@ pub trait Context: Clone + Send + Sync {
     }
 
     /// check whether there is an [`Error`] in context.
+    ///
+    /// A cancellation takes priority over a timeout.
     async fn error(&self) -> Option<Error> {
-        if self.is_cancelled().await {
-            Some(Error::ContextCancelled)
-        } else if self.is_timeout().await {
-            Some(Error::ContextTimeout)
-        } else {
-            None
-        }
+        self.timer().error().await
+    }
+
+    /// same as [`Self::deadline`], but never blocks: usable in sync code.
+    fn try_deadline(&self) -> Option<time::Instant> {
+        self.timer().try_deadline()
+    }
+
+    /// same as [`Self::is_cancelled`], but never blocks: usable in sync code.
+    ///
+    /// # Example
+    /// ```rust
+    /// use context_async::{Context, Timer};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ctx = Timer::background();
+    /// let child = ctx.clone();
+    ///
+    /// let job = tokio::task::spawn_blocking(move || {
+    ///     let mut count = 0;
+    ///     while !child.try_is_cancelled() {
+    ///         count += 1;
+    ///         std::thread::yield_now();
+    ///     }
+    ///     count
+    /// });
+    ///
+    /// ctx.cancel().await;
+    /// job.await.unwrap();
+    /// # });
+    /// ```
+    fn try_is_cancelled(&self) -> bool {
+        self.timer().try_is_cancelled()
+    }
+
+    /// same as [`Self::is_timeout`], but never blocks: usable in sync code.
+    fn try_is_timeout(&self) -> bool {
+        self.timer().try_is_timeout()
+    }
+
+    /// same as [`Self::error`], but never blocks: usable in sync code.
+    fn try_error(&self) -> Option<Error> {
+        self.timer().try_error()
+    }
+
+    /// check whether this context is cancelled or timeout, without blocking.
+    fn is_done(&self) -> bool {
+        self.try_error().is_some()
+    }
+
+    /// return the value of key `K`, looking up this context and then its ancestors.
+    /// return [None] when no context in the chain carries `K`.
+    ///
+    /// Values are attached by [`Timer::with_value`].
+    async fn value<K: Key>(&self) -> Option<Arc<K::Value>> {
+        self.timer().value::<K>().await
+    }
+
+    /// return a future that resolves with the [`Error`] once this context
+    /// is cancelled or timeout, like `<-ctx.Done()` in Go.
+    ///
+    /// The future is `'static`: it can be moved into a spawned task,
+    /// or used as a branch of `tokio::select!`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use context_async::{Context, Error, Timer};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ctx = Timer::background();
+    /// let done = ctx.done();
+    ///
+    /// let cleanup = tokio::spawn(async move {
+    ///     let err = done.await;
+    ///     // ... cleanup
+    ///     err
+    /// });
+    ///
+    /// ctx.cancel().await;
+    /// assert_eq!(cleanup.await.unwrap(), Error::ContextCancelled);
+    /// # });
+    /// ```
+    fn done(&self) -> Done {
+        self.timer().done()
     }
 
     /// spawn a new child context.
@@ -86,6 +191,9 @@ pub trait Context: Clone + Send + Sync {
     /// When the parent (self) is cancelled (call by [`Self::cancel`]),
     /// the child context will be cancelled too.
     ///
+    /// The child keeps its parent alive, but not the other way around:
+    /// once every clone of the child is dropped, it is detached from the parent.
+    ///
     /// # Example
     /// ```rust
     ///
@@ -110,7 +218,8 @@ pub trait Context: Clone + Send + Sync {
     /// Same as [`Self::spawn`], when the parent (self) is cancelled (call by [`Self::cancel`]),
     /// the child context will be cancelled too.
     ///
-    /// The expire_at instant should not be longer than the parent's expire_at.
+    /// The child's deadline is `now + timeout`, shortened to the parent's deadline
+    /// if the parent expires earlier. A parent without deadline does not limit the child.
     ///
     /// # Note
     /// see [`Self::spawn`] for more examples.
@@ -126,6 +235,40 @@ pub trait Context: Clone + Send + Sync {
         self.spawn_with_timeout(time::Duration::from_millis(millis)).await
     }
 
+    /// spawn a tokio task that runs `fut` under a new child context.
+    ///
+    /// The task stops as soon as the child context is cancelled or timeout,
+    /// and the returned [`TaskHandle`] resolves with the [`Error`].
+    /// The child is cancelled with its parent (self), or by [`TaskHandle::cancel`].
+    ///
+    /// # Panics
+    /// Panics if called outside a tokio runtime. Awaiting the handle
+    /// resumes the panic of the task, if any.
+    ///
+    /// # Example
+    /// ```rust
+    /// use context_async::{Context, Error, Timer};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ctx = Timer::background();
+    ///
+    /// let quick = ctx.spawn_task(async { 42 }).await;
+    /// let slow = ctx.spawn_task(std::future::pending::<()>()).await;
+    ///
+    /// assert_eq!(quick.await, Ok(42));
+    ///
+    /// ctx.cancel().await;
+    /// assert_eq!(slow.await, Err(Error::ContextCancelled));
+    /// # });
+    /// ```
+    async fn spawn_task<Fut>(&self, fut: Fut) -> TaskHandle<Fut::Output>
+    where
+        Fut: Future + Send + 'static,
+        Fut::Output: Send + 'static,
+    {
+        TaskHandle::spawn(self.timer().spawn().await, fut)
+    }
+
     /// handle a future
     ///
     /// # Examples
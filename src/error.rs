@@ -1,9 +1,36 @@
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Error {
     ContextCancelled,
     ContextTimeout,
+    /// The context is cancelled with a cause, see [`crate::Context::cancel_with_reason`].
+    ContextCancelledWith(Arc<dyn std::error::Error + Send + Sync>),
+}
+
+impl Error {
+    /// check whether this error is a cancellation, with or without a cause.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Self::ContextCancelled | Self::ContextCancelledWith(_))
+    }
+
+    /// check whether this error is a timeout.
+    #[inline]
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::ContextTimeout)
+    }
+
+    /// return the cause of the cancellation, if any.
+    pub fn cause(&self) -> Option<&Arc<dyn std::error::Error + Send + Sync>> {
+        match self {
+            Self::ContextCancelledWith(cause) => Some(cause),
+            _ => None,
+        }
+    }
 }
 
 impl Display for Error {
@@ -11,10 +38,55 @@ impl Display for Error {
         match self {
             Self::ContextCancelled => f.write_str("context cancelled"),
             Self::ContextTimeout => f.write_str("context timeout"),
+            Self::ContextCancelledWith(cause) => write!(f, "context cancelled: {}", cause),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ContextCancelledWith(cause) => Some(cause.as_ref()),
+            _ => None,
         }
     }
 }
 
-impl std::error::Error for Error {}
+/// Turn an [`Error`] into an [`std::io::Error`], of kind [`std::io::ErrorKind::TimedOut`]
+/// for a timeout, or [`std::io::ErrorKind::Interrupted`] for a cancellation.
+impl From<Error> for std::io::Error {
+    fn from(value: Error) -> Self {
+        let kind = if value.is_timeout() {
+            std::io::ErrorKind::TimedOut
+        } else {
+            std::io::ErrorKind::Interrupted
+        };
+
+        std::io::Error::new(kind, value)
+    }
+}
+
+/// Two [`Error::ContextCancelledWith`] are equal when they share the same cause.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::ContextCancelled, Self::ContextCancelled) => true,
+            (Self::ContextTimeout, Self::ContextTimeout) => true,
+            (Self::ContextCancelledWith(a), Self::ContextCancelledWith(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Error {}
+
+impl Hash for Error {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        if let Self::ContextCancelledWith(cause) = self {
+            (Arc::as_ptr(cause) as *const () as usize).hash(state);
+        }
+    }
+}
 
 pub type Result<T> = core::result::Result<T, Error>;
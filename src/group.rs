@@ -0,0 +1,127 @@
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use crate::{CancelGuard, Context, Error, TaskHandle, Timer};
+
+/// The [`TaskGroup`] runs a group of tasks under a shared child context,
+/// and cancels all of them on the first failure, like `errgroup` in Go.
+///
+/// Each task runs on the tokio runtime, under [`Context::handle`]. The first `Err`,
+/// returned by a task or caused by the context, cancels the group's context,
+/// and is returned by [`TaskGroup::wait`]. Dropping the group without waiting
+/// also cancels its context, and so its tasks.
+///
+/// # Examples
+/// ```rust
+/// use context_async::{Context, Error, TaskGroup, Timer};
+///
+/// # tokio_test::block_on(async {
+/// let ctx = Timer::background();
+/// let mut group = TaskGroup::<u8, Error>::new(&ctx).await;
+///
+/// group.spawn(async { Ok(1) }).await;
+/// group.spawn(async { Ok(2) }).await;
+///
+/// assert_eq!(group.wait().await, Ok(vec![1, 2]));
+/// # });
+/// ```
+pub struct TaskGroup<T, E> {
+    timer: Timer,
+    tasks: Vec<TaskHandle<Option<T>>>,
+    error: Arc<Mutex<Option<E>>>,
+    // cancels the context of the group when it is dropped.
+    _guard: CancelGuard,
+}
+
+impl<T, E> TaskGroup<T, E>
+where
+    T: Send + 'static,
+    E: From<Error> + Send + 'static,
+{
+    /// create a new group, whose context is a child of `ctx`.
+    pub async fn new<Ctx: Context>(ctx: Ctx) -> Self {
+        let timer = ctx.timer().spawn().await;
+
+        Self {
+            _guard: timer.cancel_guard(),
+            timer,
+            tasks: Vec::new(),
+            error: Default::default(),
+        }
+    }
+
+    /// return the context of the group.
+    ///
+    /// It is cancelled on the first failure, and when [`Self::wait`] returns or the group is dropped.
+    #[inline]
+    pub fn timer(&self) -> &Timer {
+        &self.timer
+    }
+
+    /// run `fut` as a new task of the group.
+    pub async fn spawn<Fut>(&mut self, fut: Fut)
+    where
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        let timer = self.timer.clone();
+        let error = self.error.clone();
+
+        let task = self.timer.spawn_task(async move {
+            match fut.await {
+                Ok(output) => Some(output),
+                Err(err) => {
+                    record(&error, err);
+                    timer.cancel().await;
+                    None
+                }
+            }
+        }).await;
+
+        self.tasks.push(task);
+    }
+
+    /// wait for all the tasks of the group, then return their outputs,
+    /// in the order they were spawned, or the first error.
+    pub async fn wait(self) -> Result<Vec<T>, E> {
+        let mut outputs = Vec::with_capacity(self.tasks.len());
+
+        for task in self.tasks {
+            match task.await {
+                Ok(Some(output)) => outputs.push(output),
+                Ok(None) => {},
+                Err(err) => {
+                    record(&self.error, E::from(err));
+                    self.timer.cancel().await;
+                },
+            }
+        }
+
+        self.timer.cancel().await;
+
+        let error = self.error.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+
+        match error {
+            Some(err) => Err(err),
+            None => Ok(outputs),
+        }
+    }
+}
+
+/// keep `err` only if it is the first error of the group.
+fn record<E>(error: &Mutex<Option<E>>, err: E) {
+    let mut error = error.lock().unwrap_or_else(PoisonError::into_inner);
+    if error.is_none() {
+        *error = Some(err);
+    }
+}
+
+impl<T, E> Debug for TaskGroup<T, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskGroup")
+            .field("timer", &self.timer)
+            .field("tasks", &self.tasks.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{Error, Timer};
+
+/// The [`CancelGuard`] cancels its context when dropped, like `defer cancel()` in Go.
+///
+/// Cancelling the context also cancels all its childs. Use [`CancelGuard::disarm`]
+/// to drop the guard without cancelling.
+///
+/// # Examples
+/// ```rust
+/// use context_async::{Context, Timer};
+///
+/// async fn work(ctx: &Timer) -> Result<(), ()> {
+///     let (child, _guard) = ctx.spawn_scoped().await;
+///     let grandchild = child.spawn().await;
+///
+///     Err(())?; // early return: `_guard` cancels `child` and `grandchild`.
+///
+///     Ok(())
+/// }
+///
+/// # tokio_test::block_on(async {
+/// let ctx = Timer::background();
+/// assert!(work(&ctx).await.is_err());
+/// assert!(!ctx.is_cancelled().await);
+/// # });
+/// ```
+#[derive(Debug)]
+#[must_use = "the context is cancelled as soon as the guard is dropped"]
+pub struct CancelGuard {
+    timer: Option<Timer>,
+}
+
+impl CancelGuard {
+    pub(crate) fn new(timer: Timer) -> Self {
+        Self { timer: Some(timer) }
+    }
+
+    /// return the context guarded by this guard.
+    pub fn timer(&self) -> &Timer {
+        self.timer.as_ref()
+            .expect("BUG: the timer is taken only by disarm or drop")
+    }
+
+    /// drop the guard without cancelling its context, return the context.
+    pub fn disarm(mut self) -> Timer {
+        self.timer.take()
+            .expect("BUG: the timer is taken only by disarm or drop")
+    }
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            timer.cancel_with_error(Error::ContextCancelled);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::time;
+#[cfg(any(feature = "actix-web-from-request", feature = "tower", feature = "axum"))]
+use crate::Timer;
+
+/// The header carrying the timeout of a request, in milliseconds.
+#[cfg(any(feature = "actix-web-from-request", feature = "tower", feature = "axum"))]
+pub(crate) const REQUEST_TIMEOUT: &str = "x-request-timeout";
+
+/// parse the value of a timeout header, which is either a plain number of milliseconds,
+/// such as `1500`, or a `grpc-timeout` value, such as `1500m` or `2S`.
+#[cfg(any(feature = "actix-web-from-request", feature = "tower", feature = "axum"))]
+pub(crate) fn parse_timeout(value: &str) -> Option<time::Duration> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if value.bytes().all(|b| b.is_ascii_digit()) {
+        return value.parse().ok().map(time::Duration::from_millis);
+    }
+
+    parse_grpc_timeout(value)
+}
+
+/// create the context of a request, with the timeout parsed from `header_value`, or `default`.
+///
+/// The context is spawned from `root` if any, otherwise it is a new root context.
+#[cfg(any(feature = "actix-web-from-request", feature = "tower", feature = "axum"))]
+pub(crate) fn request_timer(
+    header_value: Option<&str>,
+    default: Option<time::Duration>,
+    root: Option<&Timer>,
+) -> Timer {
+    let timeout = header_value.and_then(parse_timeout).or(default);
+
+    match root {
+        Some(root) => root.spawn_child(timeout),
+        None => match timeout {
+            Some(timeout) => Timer::with_timeout(timeout),
+            None => Timer::background(),
+        },
+    }
+}
+
+/// parse a `grpc-timeout` value: at most 8 digits, followed by a unit.
+pub(crate) fn parse_grpc_timeout(value: &str) -> Option<time::Duration> {
+    let unit = value.chars().last()?;
+    let digits = &value[..value.len() - unit.len_utf8()];
+    if digits.is_empty() || digits.len() > 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let amount: u64 = digits.parse().ok()?;
+    let timeout = match unit {
+        'H' => time::Duration::from_secs(amount * 60 * 60),
+        'M' => time::Duration::from_secs(amount * 60),
+        'S' => time::Duration::from_secs(amount),
+        'm' => time::Duration::from_millis(amount),
+        'u' => time::Duration::from_micros(amount),
+        'n' => time::Duration::from_nanos(amount),
+        _ => return None,
+    };
+
+    Some(timeout)
+}
+
+/// encode `timeout` as a `grpc-timeout` value, in the finest unit that fits in 8 digits.
+///
+/// The value is rounded up, so that it never expires earlier than `timeout`.
+#[cfg(feature = "tonic")]
+pub(crate) fn encode_grpc_timeout(timeout: time::Duration) -> String {
+    const MAX: u128 = 99_999_999;
+    const UNITS: [(char, u128); 6] = [
+        ('n', 1),
+        ('u', 1_000),
+        ('m', 1_000_000),
+        ('S', 1_000_000_000),
+        ('M', 60 * 1_000_000_000),
+        ('H', 60 * 60 * 1_000_000_000),
+    ];
+
+    let nanos = timeout.as_nanos();
+    for (unit, scale) in UNITS {
+        let amount = nanos.div_ceil(scale);
+        if amount <= MAX {
+            return format!("{}{}", amount, unit);
+        }
+    }
+
+    format!("{}H", MAX)
+}
@@ -0,0 +1,121 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::Poll;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use crate::{Context, Done};
+
+/// The [`ContextIo`] wraps an I/O object, and fails every read and write
+/// once its [`Context`] is cancelled or timeout.
+///
+/// The [`crate::Error`] is turned into an [`io::Error`], of kind [`io::ErrorKind::TimedOut`]
+/// for a timeout, or [`io::ErrorKind::Interrupted`] for a cancellation.
+/// Shutdown is always forwarded, so that the I/O object can still be closed.
+///
+/// # Examples
+/// ```rust
+/// use tokio::io::AsyncReadExt;
+/// use context_async::{Context, ContextIo, Timer};
+///
+/// # tokio_test::block_on(async {
+/// let ctx = Timer::background();
+/// let (client, _server) = tokio::io::duplex(64);
+/// let mut client = ContextIo::new(client, &ctx);
+///
+/// ctx.cancel().await;
+///
+/// let mut buf = Vec::new();
+/// let err = client.read_to_end(&mut buf).await.err().unwrap();
+/// assert_eq!(err.kind(), std::io::ErrorKind::Interrupted);
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct ContextIo<T> {
+    io: T,
+    done: Done,
+}
+
+impl<T> ContextIo<T> {
+    /// wrap `io` with the context `ctx`.
+    pub fn new<Ctx: Context>(io: T, ctx: Ctx) -> Self {
+        Self {
+            io,
+            done: ctx.done(),
+        }
+    }
+
+    /// return a reference to the inner I/O object.
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    /// return a mutable reference to the inner I/O object.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    /// consume this wrapper, return the inner I/O object.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.io
+    }
+
+    fn poll_done(&mut self, cx: &mut std::task::Context<'_>) -> Poll<io::Error> {
+        Pin::new(&mut self.done).poll(cx)
+            .map(io::Error::from)
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for ContextIo<T> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if let Poll::Ready(err) = this.poll_done(cx) {
+            return Poll::Ready(Err(err));
+        }
+
+        Pin::new(&mut this.io).poll_read(cx, buf)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for ContextIo<T> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if let Poll::Ready(err) = this.poll_done(cx) {
+            return Poll::Ready(Err(err));
+        }
+
+        Pin::new(&mut this.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if let Poll::Ready(err) = this.poll_done(cx) {
+            return Poll::Ready(Err(err));
+        }
+
+        Pin::new(&mut this.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>, bufs: &[io::IoSlice<'_>]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if let Poll::Ready(err) = this.poll_done(cx) {
+            return Poll::Ready(Err(err));
+        }
+
+        Pin::new(&mut this.io).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+}
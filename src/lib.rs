@@ -52,30 +52,108 @@
 //!
 //! ```
 //!
+//! ## Values
+//!
+//! A [`Timer`] can carry typed values, keyed by a type implementing [`Key`].
+//! [`Timer::with_value`] spawns a child context with the value, and
+//! [`Context::value`] looks it up through the chain of ancestors.
+//!
+//! ## Propagation
+//!
+//! [`WireContext`] captures the deadline, name, cancellation and [`Baggage`] of a context,
+//! and encodes them as HTTP headers, a binary blob or a string, to cross process boundaries.
+//! [`WireContext::into_timer`] decodes them into a new root [`Timer`].
+//!
+//! ## Introspection
+//!
+//! [`Timer::snapshot`] returns a [`Snapshot`] of the live context tree, with the age, deadline,
+//! state and childs of each context, rendered as text, JSON or Graphviz DOT.
+//!
 //! ## Error
 //!
-//! [`Context`] returns [`Error`], one of [`Error::ContextCancelled`] or [`Error::ContextTimeout`].
+//! [`Context`] returns [`Error`], one of [`Error::ContextCancelled`] or [`Error::ContextTimeout`],
+//! or [`Error::ContextCancelledWith`] when the context is cancelled with a cause
+//! by [`Context::cancel_with_reason`].
 //!
 //! ## Features
-//! - `actix-web-from-request`: implement actix-web::FromRequest for [`Timer`].
-//! - `name`: create a name for each [`Context`].
-//! - `tracing`: enable `tracing` and do `tracing::trace!(...)` logging.
+//! - `actix-web-from-request`: implement actix-web::FromRequest for [`Timer`],
+//!   which returns a request-scoped context, see `TimerConfig`, and actix-web::ResponseError
+//!   for [`Error`].
+//! - `actix-web-middleware`: provide `TimerMiddleware`, which enforces request deadlines
+//!   and turns [`Error`] into HTTP responses.
+//! - `tower`: provide `ContextLayer`, which attaches a [`Timer`] to every `http::Request`
+//!   and runs the inner service under it.
+//! - `axum`: implement axum::extract::FromRequestParts for [`Timer`].
+//! - `tonic`: propagate deadlines over gRPC with the `grpc-timeout` header,
+//!   and turn [`Error`] into `tonic::Status`.
+//! - `name`: create a name for each [`Context`], a path such as `req-42/db-7/query-3`
+//!   below the name of its parent.
+//! - `uuid`: provide `NameGenerator::uuid_v4`, to create UUIDv4 ids for names.
+//! - `ulid`: provide `NameGenerator::ulid`, to create ULID ids for names, which sort by time.
+//! - `serde`: implement `serde::Serialize` and `serde::Deserialize` for `Name`.
+//! - `metrics`: record counters of the created, cancelled and timed out contexts, and histograms
+//!   of the latency and remaining time of [`Context::handle`], labeled by `Operation`.
+//! - `tracing`: give each [`Timer`] a `tracing::Span`, a child of the span of its parent context,
+//!   which instruments [`Context::handle`], and do `tracing::trace!(...)` logging.
+//! - `stream`: implement `WithStream` to bind a `futures::Stream` to a [`Context`].
 
 mod timer;
 mod context;
 mod error;
 mod with;
+mod value;
+mod io;
+mod task;
+mod group;
+mod guard;
+pub mod wire;
+mod snapshot;
 #[cfg(feature = "name")]
 mod name;
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "actix-web-from-request")]
+mod actix;
+#[cfg(feature = "tower")]
+mod tower;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "tonic")]
+mod tonic;
+#[cfg(any(feature = "actix-web-from-request", feature = "tower", feature = "axum", feature = "tonic"))]
+mod header;
+#[cfg(feature = "actix-web-middleware")]
+mod middleware;
+#[cfg(feature = "metrics")]
+mod metrics;
 
 pub use timer::*;
 pub use context::*;
 pub use error::*;
 pub use with::*;
+pub use value::Key;
+pub use io::*;
+pub use task::*;
+pub use group::*;
+pub use guard::*;
+pub use wire::{Baggage, WireContext};
+pub use snapshot::*;
 #[cfg(feature = "name")]
 pub use name::*;
+#[cfg(feature = "stream")]
+pub use stream::*;
+#[cfg(feature = "actix-web-from-request")]
+pub use actix::TimerConfig;
+#[cfg(feature = "actix-web-middleware")]
+pub use middleware::*;
+#[cfg(feature = "tower")]
+pub use tower::*;
+#[cfg(feature = "tonic")]
+pub use tonic::{set_grpc_timeout, GrpcTimeoutInterceptor};
+#[cfg(feature = "metrics")]
+pub use metrics::Operation;
 
-/// Re-export [`async_trait`] crate.
+/// Re-export [`async_trait`](mod@async_trait) crate.
 pub use async_trait::async_trait;
 
 #[doc(hidden)]
@@ -0,0 +1,72 @@
+use std::time;
+use crate::{Error, Key};
+
+/// The [`Operation`] key labels the metrics of a context and its descendants.
+///
+/// Without it, the metrics are labeled with `unlabeled`. Names are never used as labels,
+/// as they can come from upstream requests, with an unbounded number of values.
+///
+/// The recorded metrics, all labeled with `operation`, are:
+/// - `context_async_created_total`: the created contexts.
+/// - `context_async_cancelled_total`: the cancelled contexts.
+/// - `context_async_timeout_total`: the timed out contexts, counted once per context when
+///   the timeout is observed: by a [`crate::Done`] future, a `handle`, `is_timeout` or `error` call,
+///   or at the deadline with [`crate::Timer::fire_on_deadline`].
+/// - `context_async_handle_total`: the finished [`crate::Context::handle`] calls,
+///   also labeled with their `outcome`: `ok`, `cancelled` or `timeout`.
+/// - `context_async_handle_duration_seconds`: the latency of the `handle` calls, labeled with `outcome`.
+/// - `context_async_remaining_seconds`: the time left before the deadline,
+///   when a `handle` call completes.
+///
+/// # Examples
+/// ```rust
+/// use context_async::{Context, Operation, Timer};
+///
+/// # tokio_test::block_on(async {
+/// let ctx = Timer::in_seconds(5).with_value::<Operation>("checkout".into()).await;
+///
+/// // recorded as `context_async_handle_total{operation="checkout", outcome="ok"}`.
+/// ctx.handle(async {}).await.unwrap();
+/// # });
+/// ```
+pub struct Operation;
+
+impl Key for Operation {
+    type Value = String;
+}
+
+pub(crate) const UNLABELED: &str = "unlabeled";
+
+fn outcome(error: Option<&Error>) -> &'static str {
+    match error {
+        None => "ok",
+        Some(err) if err.is_timeout() => "timeout",
+        Some(_) => "cancelled",
+    }
+}
+
+pub(crate) fn created(operation: &str) {
+    metrics::counter!("context_async_created_total", "operation" => operation.to_string()).increment(1);
+}
+
+pub(crate) fn cancelled(operation: &str) {
+    metrics::counter!("context_async_cancelled_total", "operation" => operation.to_string()).increment(1);
+}
+
+pub(crate) fn timed_out(operation: &str) {
+    metrics::counter!("context_async_timeout_total", "operation" => operation.to_string()).increment(1);
+}
+
+pub(crate) fn handled(operation: &str, error: Option<&Error>, elapsed: time::Duration, remaining: Option<time::Duration>) {
+    let outcome = outcome(error);
+
+    metrics::counter!("context_async_handle_total", "operation" => operation.to_string(), "outcome" => outcome)
+        .increment(1);
+    metrics::histogram!("context_async_handle_duration_seconds", "operation" => operation.to_string(), "outcome" => outcome)
+        .record(elapsed.as_secs_f64());
+
+    if let (None, Some(remaining)) = (error, remaining) {
+        metrics::histogram!("context_async_remaining_seconds", "operation" => operation.to_string())
+            .record(remaining.as_secs_f64());
+    }
+}
@@ -0,0 +1,164 @@
+use std::future::{poll_fn, Future};
+use std::pin::{pin, Pin};
+use std::rc::Rc;
+use std::task::Poll;
+use std::time;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::StatusCode;
+use actix_web::error::InternalError;
+use crate::actix::scope_request_timer;
+use crate::{Context, Error, TimerConfig};
+
+/// The [`TimerMiddleware`] runs the rest of the service chain under the request's [`crate::Timer`],
+/// and turns its [`Error`] into an error response.
+///
+/// The request context is created as by the [`crate::Timer`] extractor, see [`crate::TimerConfig`],
+/// then shortened to the timeout of the middleware. Handlers extracting a [`crate::Timer`]
+/// receive this context.
+///
+/// Its [`Error`], and an [`Error`] returned by the service chain, become an error response
+/// with the statuses of the [`crate::TimerConfig`], unless set on the middleware. By default,
+/// [`Error::ContextTimeout`] becomes `504 Gateway Timeout`, and a cancellation
+/// becomes `503 Service Unavailable`.
+///
+/// # Examples
+/// ```rust
+/// use std::time;
+/// use actix_web::{web, App};
+/// use context_async::{Timer, TimerMiddleware};
+///
+/// async fn index(ctx: Timer) -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = App::new()
+///     .service(
+///         web::resource("/")
+///             .wrap(TimerMiddleware::new(time::Duration::from_secs(3)))
+///             .route(web::get().to(index))
+///     );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TimerMiddleware {
+    timeout: Option<time::Duration>,
+    timeout_status: Option<StatusCode>,
+    cancelled_status: Option<StatusCode>,
+}
+
+impl TimerMiddleware {
+    /// create a middleware, which limits each request to `timeout`.
+    pub fn new(timeout: time::Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..Default::default()
+        }
+    }
+
+    /// create a middleware, which doesn't limit the requests more than the [`crate::TimerConfig`].
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// set the status of the response when the request context is timeout,
+    /// instead of the one of the [`crate::TimerConfig`].
+    pub fn timeout_status(mut self, status: StatusCode) -> Self {
+        self.timeout_status = Some(status);
+        self
+    }
+
+    /// set the status of the response when the request context is cancelled,
+    /// such as `499 Client Closed Request`, instead of the one of the [`crate::TimerConfig`].
+    pub fn cancelled_status(mut self, status: StatusCode) -> Self {
+        self.cancelled_status = Some(status);
+        self
+    }
+
+    /// return the status of the response for `err`.
+    fn status(&self, config: &TimerConfig, err: &Error) -> StatusCode {
+        let status = if err.is_timeout() {
+            self.timeout_status
+        } else {
+            self.cancelled_status
+        };
+
+        status.unwrap_or_else(|| config.status(err))
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for TimerMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = TimerMiddlewareService<S>;
+    type InitError = ();
+    type Future = std::future::Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        std::future::ready(Ok(TimerMiddlewareService {
+            service: Rc::new(service),
+            config: self.clone(),
+        }))
+    }
+}
+
+/// The service created by [`TimerMiddleware`].
+#[derive(Debug)]
+pub struct TimerMiddlewareService<S> {
+    service: Rc<S>,
+    config: TimerMiddleware,
+}
+
+impl<S, B> Service<ServiceRequest> for TimerMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let config = self.config.clone();
+
+        Box::pin(async move {
+            let timer = scope_request_timer(req.request(), config.timeout);
+            let timer_config = TimerConfig::of(req.request());
+
+            // `Context::handle` requires a `Send` future, which the service chain is not.
+            let mut done = timer.done();
+            let mut fut = pin!(service.call(req));
+            let result = poll_fn(|cx| {
+                if let Poll::Ready(err) = Pin::new(&mut done).poll(cx) {
+                    return Poll::Ready(Err(err));
+                }
+
+                fut.as_mut().poll(cx).map(Ok)
+            }).await;
+
+            match result {
+                Ok(Ok(mut res)) => {
+                    // an `Error` returned by a handler, turned into a response by `ResponseError`.
+                    let status = res.response().error()
+                        .and_then(|err| err.as_error::<Error>())
+                        .map(|err| config.status(&timer_config, err));
+                    if let Some(status) = status {
+                        *res.response_mut().status_mut() = status;
+                    }
+
+                    Ok(res)
+                },
+                Ok(Err(err)) => Err(err),
+                Err(err) => {
+                    let status = config.status(&timer_config, &err);
+                    Err(InternalError::new(err, status).into())
+                },
+            }
+        })
+    }
+}
@@ -1,23 +1,305 @@
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 
-#[derive(Debug, Clone, Copy)]
-pub struct Name(u64);
+/// The [`Name`] of a context, a path from the root context such as `req-42/db-7/query-3`.
+///
+/// Each segment is the label given at creation or spawn time, followed by an id
+/// created by the [`NameGenerator`], which keeps it unique. A segment without label is the id alone.
+///
+/// A [`Name`] is displayed as its path, and parsed back from it. An opaque id from
+/// upstream, such as an `X-Request-Id` header, is turned into a root name by [`Name::from_id`]
+/// and given to [`crate::Timer::with_name`].
+///
+/// # Examples
+/// ```rust
+/// use context_async::{Context, Timer};
+///
+/// # tokio_test::block_on(async {
+/// let ctx = Timer::named("req");
+/// let db = ctx.spawn_named("db").await;
+/// let query = db.spawn().await;
+///
+/// let name = query.name().await;
+/// assert_eq!(name.parent(), Some(&db.name().await));
+/// assert_eq!(name.ancestors().count(), 3);
+/// assert_eq!(name.root().label(), Some("req"));
+/// assert!(name.to_string().starts_with("req-"));
+/// assert_eq!(name.to_string().parse(), Ok(name));
+/// # });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Name(Arc<Segment>);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Segment {
+    id: Arc<str>,
+    label: Option<Arc<str>>,
+    parent: Option<Name>,
+}
 
 impl Default for Name {
     fn default() -> Self {
-        Self(rand::random())
+        NameGenerator::global().generate()
     }
 }
 
 impl Display for Name {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0.to_string())
-    }    
+        if let Some(parent) = &self.0.parent {
+            write!(f, "{}/", parent)?;
+        }
+
+        match &self.0.label {
+            Some(label) => write!(f, "{}-{}", label, self.0.id),
+            // a leading `-` keeps an id containing `-` from being parsed as a label.
+            None if self.0.id.contains('-') => write!(f, "-{}", self.0.id),
+            None => f.write_str(&self.0.id),
+        }
+    }
+}
+
+/// The error of parsing a malformed [`Name`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ParseNameError;
+
+impl Display for ParseNameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("malformed context name")
+    }
+}
+
+impl std::error::Error for ParseNameError {}
+
+/// Parse a [`Name`] from its path, as displayed.
+///
+/// Each segment is split at its first `-`, into a label and an id. Use [`Name::from_id`]
+/// for an id which is not a name displayed by this crate, such as a hyphenated UUID.
+impl FromStr for Name {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name: Option<Name> = None;
+
+        for segment in s.split('/') {
+            let (label, id) = match segment.split_once('-') {
+                Some((label, id)) => ((!label.is_empty()).then_some(label), id),
+                None => (None, segment),
+            };
+
+            if id.is_empty() {
+                return Err(ParseNameError);
+            }
+
+            name = Some(Self(Arc::new(Segment {
+                id: id.into(),
+                label: label.map(Arc::from),
+                parent: name,
+            })));
+        }
+
+        name.ok_or(ParseNameError)
+    }
+}
+
+impl From<u64> for Name {
+    fn from(value: u64) -> Self {
+        Self::new(value.to_string().into())
+    }
 }
 
 impl Name {
+    /// create a root name without label from an opaque `id`, which is not parsed.
+    ///
+    /// A `/` in the id is replaced by `_`, and an empty id by `_`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use context_async::Name;
+    ///
+    /// let name = Name::from_id("550e8400-e29b-41d4-a716-446655440000");
+    /// assert_eq!(name.label(), None);
+    /// assert_eq!(name.id(), "550e8400-e29b-41d4-a716-446655440000");
+    /// assert_eq!(name.to_string().parse(), Ok(name));
+    /// ```
+    pub fn from_id(id: &str) -> Self {
+        Self::new(id.into())
+    }
+
+    /// create a root name with `id`. A `/` in the id is replaced by `_`, and an empty id by `_`.
+    pub(crate) fn new(id: Arc<str>) -> Self {
+        let id = match id.is_empty() {
+            true => Arc::from("_"),
+            false => sanitize(id, &['/']),
+        };
+
+        Self(Arc::new(Segment {
+            id,
+            label: None,
+            parent: None,
+        }))
+    }
+
+    /// label the last segment with `label`. A `/` or `-` in the label is replaced by `_`.
+    pub(crate) fn set_label(&mut self, label: Arc<str>) {
+        Arc::make_mut(&mut self.0).label = (!label.is_empty())
+            .then(|| sanitize(label, &['/', '-']));
+    }
+
+    /// make this name a child of `parent`.
+    pub(crate) fn set_parent(&mut self, parent: Name) {
+        Arc::make_mut(&mut self.0).parent = Some(parent);
+    }
+
+    /// return the id of the last segment.
     #[inline]
+    pub fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    /// return the id of the last segment as a `u64`, or a hash of the id if it is not a number.
+    #[deprecated(note = "a name is a path of string ids, use `Name::id` instead")]
     pub fn as_u64(&self) -> u64 {
-        self.0
+        self.id().parse().unwrap_or_else(|_| {
+            let mut hasher = std::hash::DefaultHasher::new();
+            self.id().hash(&mut hasher);
+            hasher.finish()
+        })
+    }
+
+    /// return the label of the last segment.
+    #[inline]
+    pub fn label(&self) -> Option<&str> {
+        self.0.label.as_deref()
+    }
+
+    /// return the name of the parent context.
+    #[inline]
+    pub fn parent(&self) -> Option<&Name> {
+        self.0.parent.as_ref()
+    }
+
+    /// return an iterator over this name and the names of its ancestors, up to the root.
+    pub fn ancestors(&self) -> impl Iterator<Item = &Name> {
+        std::iter::successors(Some(self), |name| name.parent())
+    }
+
+    /// return the name of the root context.
+    pub fn root(&self) -> &Name {
+        self.ancestors().last().unwrap_or(self)
+    }
+
+    /// return the number of ancestors of this name, `0` for a root context.
+    pub fn depth(&self) -> usize {
+        self.ancestors().count() - 1
+    }
+}
+
+fn sanitize(value: Arc<str>, reserved: &[char]) -> Arc<str> {
+    match value.contains(reserved) {
+        true => value.replace(reserved, "_").into(),
+        false => value,
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Name {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Name {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+static GLOBAL: RwLock<Option<NameGenerator>> = RwLock::new(None);
+
+/// The [`NameGenerator`] creates the ids of [`Name`]s.
+///
+/// A generator is set process-wide with [`NameGenerator::set_global`], or for a tree of contexts
+/// with [`crate::Timer::with_name_generator`]: the childs use the generator of their root.
+/// The default generator creates random `u64` ids.
+///
+/// # Examples
+/// ```rust
+/// use context_async::{Context, NameGenerator, Timer};
+///
+/// # tokio_test::block_on(async {
+/// let ctx = Timer::with_name_generator(NameGenerator::counter());
+/// let child = ctx.spawn_named("db").await;
+///
+/// assert_eq!(ctx.name().await.to_string(), "1");
+/// assert_eq!(child.name().await.to_string(), "1/db-2");
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct NameGenerator(Arc<dyn Fn() -> String + Send + Sync>);
+
+impl Debug for NameGenerator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("NameGenerator").finish_non_exhaustive()
+    }
+}
+
+impl Default for NameGenerator {
+    fn default() -> Self {
+        Self::random()
+    }
+}
+
+impl NameGenerator {
+    /// create random `u64` ids.
+    pub fn random() -> Self {
+        Self::from_fn(|| rand::random::<u64>().to_string())
+    }
+
+    /// create sequential ids, `1`, `2`, `3`..., counted by this generator and its clones.
+    pub fn counter() -> Self {
+        let counter = AtomicU64::new(1);
+        Self::from_fn(move || counter.fetch_add(1, Ordering::Relaxed).to_string())
+    }
+
+    /// create random UUIDv4 ids, in the simple format without hyphens.
+    #[cfg(feature = "uuid")]
+    pub fn uuid_v4() -> Self {
+        Self::from_fn(|| uuid::Uuid::new_v4().simple().to_string())
+    }
+
+    /// create ULID ids, which sort by creation time.
+    #[cfg(feature = "ulid")]
+    pub fn ulid() -> Self {
+        Self::from_fn(|| ulid::Ulid::new().to_string())
+    }
+
+    /// create ids with `f`. A `/` in an id is replaced by `_`, and an empty id by `_`.
+    pub fn from_fn<F>(f: F) -> Self
+    where
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    /// set the process-wide generator, used by the contexts without their own generator.
+    pub fn set_global(self) {
+        *GLOBAL.write().unwrap_or_else(PoisonError::into_inner) = Some(self);
+    }
+
+    /// return the process-wide generator.
+    pub fn global() -> Self {
+        GLOBAL.read().unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .unwrap_or_default()
+    }
+
+    /// create a root [`Name`] with a new id.
+    pub fn generate(&self) -> Name {
+        Name::new((self.0)().into())
     }
 }
@@ -0,0 +1,186 @@
+use std::fmt::{Display, Formatter, Write};
+use std::time;
+
+/// The [`Snapshot`] of a live context tree, returned by [`crate::Timer::snapshot`].
+///
+/// It is rendered as an indented text by [`Display`], as JSON by [`Snapshot::to_json`],
+/// or as a Graphviz graph by [`Snapshot::to_dot`], for a debug endpoint or a panic hook.
+///
+/// # Examples
+/// ```rust
+/// use context_async::{Context, Timer};
+///
+/// # tokio_test::block_on(async {
+/// let ctx = Timer::in_seconds(5);
+/// let child = ctx.spawn().await;
+/// child.cancel().await;
+///
+/// let snapshot = ctx.snapshot();
+/// assert_eq!(snapshot.childs.len(), 1);
+/// assert!(snapshot.childs[0].cancelled);
+///
+/// println!("{}", snapshot);
+/// println!("{}", snapshot.to_json());
+/// println!("{}", snapshot.to_dot());
+/// # });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// the name of the context, with the `name` feature.
+    pub name: Option<String>,
+    /// the time since the context was created.
+    pub age: time::Duration,
+    /// the time left before the deadline, `None` if the context has no deadline.
+    pub remaining: Option<time::Duration>,
+    /// whether the context is cancelled.
+    pub cancelled: bool,
+    /// whether the deadline of the context has passed.
+    pub timeout: bool,
+    /// the snapshots of the live childs.
+    pub childs: Vec<Snapshot>,
+}
+
+impl Snapshot {
+    fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or("context")
+    }
+
+    fn state(&self) -> &'static str {
+        match (self.cancelled, self.timeout) {
+            (true, _) => "cancelled",
+            (false, true) => "timeout",
+            (false, false) => "active",
+        }
+    }
+
+    /// return the number of contexts in this tree.
+    pub fn count(&self) -> usize {
+        1 + self.childs.iter().map(Snapshot::count).sum::<usize>()
+    }
+
+    /// render this tree as JSON. Durations are in milliseconds.
+    ///
+    /// ```json
+    /// {"name":null,"age_ms":12,"remaining_ms":4988,"cancelled":false,"timeout":false,"childs":[]}
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, json: &mut String) {
+        json.push_str("{\"name\":");
+        match &self.name {
+            Some(name) => write_json_string(json, name),
+            None => json.push_str("null"),
+        }
+
+        let _ = write!(json, ",\"age_ms\":{},\"remaining_ms\":", self.age.as_millis());
+        match self.remaining {
+            Some(remaining) => { let _ = write!(json, "{}", remaining.as_millis()); },
+            None => json.push_str("null"),
+        }
+
+        let _ = write!(json, ",\"cancelled\":{},\"timeout\":{},\"childs\":[", self.cancelled, self.timeout);
+        for (i, child) in self.childs.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            child.write_json(json);
+        }
+        json.push_str("]}");
+    }
+
+    /// render this tree as a Graphviz `digraph`.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph contexts {\n    node [shape=box];\n");
+        self.write_dot(&mut dot, &mut 0);
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// write the node of this context and its childs, and return the id of the node.
+    fn write_dot(&self, dot: &mut String, next: &mut usize) -> usize {
+        let id = *next;
+        *next += 1;
+
+        let mut label = format!("{}\\nage {:?}", escape_dot(self.label()), self.age);
+        if let Some(remaining) = self.remaining {
+            let _ = write!(label, "\\nremaining {:?}", remaining);
+        }
+        let _ = write!(label, "\\n{}", self.state());
+
+        let style = match self.cancelled || self.timeout {
+            true => ", style=dashed",
+            false => "",
+        };
+        let _ = writeln!(dot, "    n{} [label=\"{}\"{}];", id, label, style);
+
+        for child in &self.childs {
+            let child = child.write_dot(dot, next);
+            let _ = writeln!(dot, "    n{} -> n{};", id, child);
+        }
+
+        id
+    }
+
+    fn fmt_indented(&self, f: &mut Formatter<'_>, depth: usize) -> std::fmt::Result {
+        write!(f, "{:indent$}{} age={:?}", "", self.label(), self.age, indent = depth * 2)?;
+        match self.remaining {
+            Some(remaining) => write!(f, " remaining={:?}", remaining)?,
+            None => f.write_str(" remaining=none")?,
+        }
+        writeln!(f, " state={} childs={}", self.state(), self.childs.len())?;
+
+        for child in &self.childs {
+            child.fmt_indented(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Render the tree as an indented text, one context per line, such as
+/// `req-1 age=1.2s remaining=3.8s state=active childs=2`.
+impl Display for Snapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+fn write_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => { let _ = write!(json, "\\u{:04x}", c as u32); },
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Snapshot {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Snapshot", 6)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("age_ms", &(self.age.as_millis() as u64))?;
+        state.serialize_field("remaining_ms", &self.remaining.map(|remaining| remaining.as_millis() as u64))?;
+        state.serialize_field("cancelled", &self.cancelled)?;
+        state.serialize_field("timeout", &self.timeout)?;
+        state.serialize_field("childs", &self.childs)?;
+        state.end()
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use futures_core::Stream;
+use crate::{Context, Done};
+
+/// The [`WithStream`] trait binds a [`Stream`] to a [`Context`].
+///
+/// # Examples
+/// ```rust
+/// use futures::StreamExt;
+/// use context_async::{Context, Error, Timer, WithStream};
+///
+/// # tokio_test::block_on(async {
+/// let ctx = Timer::background();
+/// let mut stream = futures::stream::iter(0..10).with_context(&ctx);
+///
+/// assert_eq!(stream.next().await, Some(Ok(0)));
+///
+/// ctx.cancel().await;
+/// assert_eq!(stream.next().await, Some(Err(Error::ContextCancelled)));
+/// assert_eq!(stream.next().await, None);
+/// # });
+/// ```
+pub trait WithStream: Stream + Sized {
+    /// wrap this stream, so that it yields `Ok(item)`, and ends with `Err(error)`
+    /// as its final item as soon as the context is cancelled or timeout.
+    fn with_context<Ctx: Context>(self, ctx: Ctx) -> ContextStream<Self> {
+        ContextStream {
+            stream: Box::pin(self),
+            done: Some(ctx.done()),
+        }
+    }
+
+    /// wrap this stream, so that it simply stops as soon as the context is cancelled or timeout.
+    fn until_done<Ctx: Context>(self, ctx: Ctx) -> UntilDone<Self> {
+        UntilDone {
+            stream: self.with_context(ctx),
+        }
+    }
+}
+
+impl<S: Stream> WithStream for S {}
+
+/// A [`Stream`] bound to a [`Context`], see [`WithStream::with_context`].
+#[must_use = "streams do nothing unless polled"]
+pub struct ContextStream<S: Stream> {
+    stream: Pin<Box<S>>,
+    // `None` once the stream has ended.
+    done: Option<Done>,
+}
+
+impl<S: Stream> Stream for ContextStream<S> {
+    type Item = crate::Result<S::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        let Some(done) = this.done.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        if let Poll::Ready(err) = Pin::new(done).poll(cx) {
+            this.done = None;
+            return Poll::Ready(Some(Err(err)));
+        }
+
+        match this.stream.as_mut().poll_next(cx) {
+            Poll::Ready(Some(item)) => Poll::Ready(Some(Ok(item))),
+            Poll::Ready(None) => {
+                this.done = None;
+                Poll::Ready(None)
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.done {
+            Some(_) => (0, self.stream.size_hint().1.and_then(|upper| upper.checked_add(1))),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<S: Stream> Debug for ContextStream<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContextStream")
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`Stream`] which stops when its [`Context`] is done, see [`WithStream::until_done`].
+#[must_use = "streams do nothing unless polled"]
+pub struct UntilDone<S: Stream> {
+    stream: ContextStream<S>,
+}
+
+impl<S: Stream> Stream for UntilDone<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().stream)
+            .poll_next(cx)
+            .map(|item| item.and_then(Result::ok))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+impl<S: Stream> Debug for UntilDone<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UntilDone")
+            .field("stream", &self.stream)
+            .finish()
+    }
+}
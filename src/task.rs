@@ -0,0 +1,75 @@
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use tokio::task::JoinHandle;
+use crate::{Context, Error, Timer};
+
+/// A handle to a tokio task bound to a child [`Timer`], see [`Context::spawn_task`].
+///
+/// Awaiting the handle returns the output of the task, or the [`Error`]
+/// of its context. Dropping the handle detaches the task, which keeps running
+/// until it finishes or its context is done.
+#[must_use = "dropping the handle detaches the task"]
+pub struct TaskHandle<T> {
+    timer: Timer,
+    join: JoinHandle<crate::Result<T>>,
+}
+
+impl<T: Send + 'static> TaskHandle<T> {
+    pub(crate) fn spawn<Fut>(timer: Timer, fut: Fut) -> Self
+    where
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let ctx = timer.clone();
+        let join = tokio::spawn(async move {
+            ctx.handle(fut).await
+        });
+
+        Self { timer, join }
+    }
+}
+
+impl<T> TaskHandle<T> {
+    /// return the context of the task.
+    #[inline]
+    pub fn timer(&self) -> &Timer {
+        &self.timer
+    }
+
+    /// cancel the context of the task, which stops the task.
+    pub async fn cancel(&self) {
+        self.timer.cancel().await
+    }
+
+    /// check whether the task is finished.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.join.is_finished()
+    }
+}
+
+impl<T> Future for TaskHandle<T> {
+    type Output = crate::Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        Pin::new(&mut this.join).poll(cx)
+            .map(|result| match result {
+                Ok(output) => output,
+                Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+                // the runtime is shutting down.
+                Err(_) => Err(this.timer.try_error().unwrap_or(Error::ContextCancelled)),
+            })
+    }
+}
+
+impl<T> Debug for TaskHandle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskHandle")
+            .field("timer", &self.timer)
+            .field("finished", &self.join.is_finished())
+            .finish()
+    }
+}
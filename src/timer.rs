@@ -1,59 +1,161 @@
-use std::future::Future;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::future::{poll_fn, Future};
 use std::pin::{Pin, pin};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError, Weak};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::task::Poll;
 use std::time;
 use log::error;
 use tokio::sync;
-use tokio::sync::RwLock;
-use tokio::time::Sleep;
-use crate::{Context, Error};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{Instant, Sleep};
+use crate::{CancelGuard, Context, Error, Key, Snapshot};
+use crate::value::Values;
 #[cfg(feature = "name")]
-use crate::name::Name;
+use crate::name::{Name, NameGenerator};
+
+/// the id of the next context, the key of a child in the registry of its parent.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
 
 /// The [`Timer`] structure is the default [`Context`].
+///
+/// The state of a [`Timer`] is read without locking: the deadline is fixed at creation,
+/// and the cancellation is a write-once cell. Only the registry of children is locked,
+/// briefly and never across an `.await`.
+///
+/// Deadlines are measured on tokio's clock, so a [`Timer`] follows paused time
+/// in tests, with `#[tokio::test(start_paused = true)]` and `tokio::time::advance`.
 #[derive(Debug, Clone)]
 pub struct Timer {
-    inner: Arc<RwLock<Inner>>,
+    inner: Arc<Inner>,
 }
 
 #[derive(Debug)]
 struct Inner {
+    id: u64,
     #[cfg(feature = "name")]
     name: Name,
-    expire_at: Option<time::Instant>,
-    cancelled: bool,
-    cancelled_sender: sync::broadcast::Sender<()>,
-    cancelled_receiver: sync::broadcast::Receiver<()>,
-    childs: Vec<Timer>,
+    #[cfg(feature = "name")]
+    generator: Option<NameGenerator>,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "metrics")]
+    operation: String,
+    created_at: Instant,
+    expire_at: Option<Instant>,
+    cancelled: OnceLock<Error>,
+    cancelled_sender: sync::broadcast::Sender<Error>,
+    parent: Option<Timer>,
+    childs: Mutex<HashMap<u64, Weak<Inner>>>,
+    values: Option<Arc<Values>>,
+    active: AtomicBool,
+    timeout_observed: AtomicBool,
 }
 
 impl Inner {
     fn new() -> Self {
-        let (sender, receiver) = sync::broadcast::channel(32);
+        Self::build(
+            #[cfg(feature = "name")]
+            Name::default(),
+            #[cfg(feature = "name")]
+            None,
+            #[cfg(feature = "tracing")]
+            None,
+        )
+    }
 
+    /// create a child of `parent`, named by the generator of `parent`.
+    fn child_of(parent: &Inner) -> Self {
         #[cfg(feature = "name")]
-        let name = Name::default();
+        let name = {
+            let mut name = match &parent.generator {
+                Some(generator) => generator.generate(),
+                None => Name::default(),
+            };
+            name.set_parent(parent.name.clone());
+            name
+        };
+
+        #[cfg(not(any(feature = "name", feature = "tracing")))]
+        let _ = parent;
+
+        Self::build(
+            #[cfg(feature = "name")]
+            name,
+            #[cfg(feature = "name")]
+            parent.generator.clone(),
+            #[cfg(feature = "tracing")]
+            Some(&parent.span),
+        )
+    }
+
+    /// create a new context. Its span is a child of `parent_span`, or of the current span.
+    fn build(
+        #[cfg(feature = "name")] name: Name,
+        #[cfg(feature = "name")] generator: Option<NameGenerator>,
+        #[cfg(feature = "tracing")] parent_span: Option<&tracing::Span>,
+    ) -> Self {
+        let (sender, _) = sync::broadcast::channel(32);
 
         #[cfg(feature = "tracing")]
-        {
+        let span = {
             #[cfg(feature = "name")]
-            tracing::trace!(context_new=name.as_u64());
+            tracing::trace!(context_new=%name);
 
             #[cfg(not(feature = "name"))]
             tracing::trace!(context_new="");
-        }
+
+            let parent_span = match parent_span {
+                Some(span) => span.id(),
+                None => tracing::Span::current().id(),
+            };
+
+            tracing::info_span!(
+                parent: parent_span,
+                "context",
+                name = tracing::field::Empty,
+                deadline = tracing::field::Empty,
+                outcome = tracing::field::Empty,
+            )
+        };
 
         Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
             #[cfg(feature = "name")]
             name,
+            #[cfg(feature = "name")]
+            generator,
+            #[cfg(feature = "tracing")]
+            span,
+            #[cfg(feature = "metrics")]
+            operation: String::new(),
+            created_at: Instant::now(),
             expire_at: None,
-            cancelled: false,
+            cancelled: OnceLock::new(),
             cancelled_sender: sender,
-            cancelled_receiver: receiver,
+            parent: None,
             childs: Default::default(),
+            values: None,
+            active: AtomicBool::new(false),
+            timeout_observed: AtomicBool::new(false),
         }
     }
+
+    /// the label of the metrics: the [`crate::Operation`] value, or `unlabeled`.
+    ///
+    /// Names are not used, as they can come from upstream and have unbounded values.
+    #[cfg(feature = "metrics")]
+    fn operation(&self) -> String {
+        self.values.as_ref()
+            .and_then(|values| values.get::<crate::Operation>())
+            .map(|operation| (*operation).clone())
+            .unwrap_or_else(|| String::from(crate::metrics::UNLABELED))
+    }
+
+    fn childs(&self) -> MutexGuard<'_, HashMap<u64, Weak<Inner>>> {
+        self.childs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 #[async_trait::async_trait]
@@ -66,117 +168,138 @@ impl Context for Timer {
 
     #[cfg(feature = "name")]
     async fn name(&self) -> Name {
-        self.inner.read().await.name
+        self.inner.name.clone()
     }
 
     async fn deadline(&self) -> Option<time::Instant> {
-        self.inner.read().await
-            .expire_at
+        self.try_deadline()
     }
 
     async fn cancel(&self) {
-        let mut inner = self.inner.write().await;
-        if !inner.cancelled {
-            inner.cancelled = true;
-            let _ = inner.cancelled_sender.send(());
+        self.cancel_with_error(Error::ContextCancelled)
+    }
 
-            for child in &inner.childs {
-                child.cancel().await;
-            }
-        }
+    async fn cancel_with_reason(&self, reason: Arc<dyn std::error::Error + Send + Sync>) {
+        self.cancel_with_error(Error::ContextCancelledWith(reason))
     }
 
     async fn is_cancelled(&self) -> bool {
-       self.inner.read().await.cancelled
+        self.try_is_cancelled()
     }
 
     async fn is_timeout(&self) -> bool {
-        self.inner.read().await.expire_at
-            .is_some_and(|expire_at| expire_at < time::Instant::now())
+        self.try_is_timeout()
     }
 
-    async fn spawn(&self) -> Self {
-        let mut inner = self.inner.write().await;
+    async fn error(&self) -> Option<Error> {
+        self.try_error()
+    }
 
-        let mut child = Inner::new();
-        child.expire_at = inner.expire_at;
+    fn try_deadline(&self) -> Option<time::Instant> {
+        self.inner.expire_at.map(Instant::into_std)
+    }
 
-        #[cfg(feature = "tracing")]
-        {
-            #[cfg(feature = "name")]
-            tracing::trace!(context_spawn=inner.name.as_u64(), child=child.name.as_u64(), expire_at=?child.expire_at);
-            #[cfg(not(feature = "name"))]
-            tracing::trace!(context_spawn="", expire_at=?child.expire_at)
-        }
+    fn try_is_cancelled(&self) -> bool {
+        self.inner.cancelled.get().is_some()
+    }
+
+    fn try_is_timeout(&self) -> bool {
+        let timeout = self.expired();
 
-        let child_timer = Self::from(child);
-        inner.childs.push(child_timer.clone());
+        if timeout {
+            self.observe_timeout();
+        }
 
-        child_timer
+        timeout
     }
 
-    async fn spawn_with_timeout(&self, timeout: time::Duration) -> Self {
-        let mut inner = self.inner.write().await;
-
-        let child_expire_at = time::Instant::now() + timeout;
-        let child_expire_at = if let Some(expire_at) = inner.expire_at {
-            if child_expire_at > expire_at {
-                Some(expire_at)
-            } else {
-                Some(child_expire_at)
-            }
+    fn try_error(&self) -> Option<Error> {
+        let error = if let Some(err) = self.inner.cancelled.get() {
+            Some(err.clone())
+        } else if self.expired() {
+            Some(Error::ContextTimeout)
         } else {
             None
         };
 
-        let mut child = Inner::new();
-        child.expire_at = child_expire_at;
-
-        #[cfg(feature = "tracing")]
-        {
-            #[cfg(feature = "name")]
-            tracing::trace!(context_spawn=inner.name.as_u64(), with_timeout=?timeout, child=child.name.as_u64(), expire_at=?child.expire_at);
-            #[cfg(not(feature = "name"))]
-            tracing::trace!(context_spawn="", with_timeout=?timeout, expire_at=?child.expire_at)
+        if error.as_ref().is_some_and(Error::is_timeout) {
+            self.observe_timeout();
         }
 
-        let child_timer = Self::from(child);
-        inner.childs.push(child_timer.clone());
+        error
+    }
 
-        child_timer
+    fn done(&self) -> Done {
+        Done::new(self)
+    }
+
+    async fn spawn(&self) -> Self {
+        self.spawn_child(None)
+    }
+
+    async fn spawn_with_timeout(&self, timeout: time::Duration) -> Self {
+        self.spawn_child(Some(timeout))
+    }
+
+    async fn value<K: Key>(&self) -> Option<Arc<K::Value>> {
+        self.inner.values
+            .as_ref()
+            .and_then(|values| values.get::<K>())
     }
 
     async fn handle<'a, Fut, Output>(&self, fut: Fut) -> crate::Result<Output>
     where
         Fut: Future<Output = Output> + Send + 'a
     {
-        if self.is_cancelled().await {
-            return Err(Error::ContextCancelled);
-        } else if self.is_timeout().await {
-            return Err(Error::ContextTimeout);
-        }
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
 
-        let sleep = self.deadline().await
-            .map(tokio::time::Instant::from_std)
-            .map(tokio::time::sleep_until)
-            .map(Box::pin);
+        // the timeout of a `done` resolved at creation is recorded by `try_error`.
+        let done = self.done();
+        if let Some(err) = done.error.clone() {
+            #[cfg(feature = "metrics")]
+            crate::metrics::handled(&self.inner.operation, Some(&err), time::Duration::ZERO, None);
 
-        let mut cancel_receiver = self.cancel_receiver().await;
-        let cancel_receiver_fut = cancel_receiver.recv();
+            return Err(err);
+        }
+
+        #[cfg(feature = "tracing")]
+        let fut = tracing::Instrument::instrument(fut, self.inner.span.clone());
 
         let task = Task {
-            cancel_receiver: Box::pin(cancel_receiver_fut),
-            sleep,
+            done,
             fut: Box::pin(fut),
         };
 
-        task.await
+        let result = task.await;
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::handled(&self.inner.operation, result.as_ref().err(), started.elapsed(), self.remaining());
+
+        result
     }
 }
 
 impl From<Inner> for Timer {
-    fn from(value: Inner) -> Self {
-        Self { inner: Arc::new(RwLock::new(value)) }
+    #[cfg_attr(not(feature = "metrics"), allow(unused_mut))]
+    fn from(mut value: Inner) -> Self {
+        // the name and deadline are final once the context is created.
+        #[cfg(feature = "tracing")]
+        {
+            #[cfg(feature = "name")]
+            value.span.record("name", tracing::field::display(&value.name));
+            if let Some(expire_at) = value.expire_at {
+                value.span.record("deadline", tracing::field::debug(expire_at.saturating_duration_since(Instant::now())));
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            value.operation = value.operation();
+            crate::metrics::created(&value.operation);
+        }
+
+        Self { inner: Arc::new(value) }
     }
 }
 
@@ -197,7 +320,124 @@ impl Timer {
     #[inline]
     pub fn with_timeout(timeout: time::Duration) -> Self {
         let mut inner = Inner::new();
-        inner.expire_at = Some(time::Instant::now() + timeout);
+        inner.expire_at = Some(Instant::now() + timeout);
+
+        Self::from(inner)
+    }
+
+    /// create a root context, from the state decoded by [`crate::WireContext`].
+    pub(crate) fn from_wire(
+        timeout: Option<time::Duration>,
+        values: Option<Arc<Values>>,
+        #[cfg(feature = "name")] name: Option<Name>,
+    ) -> Self {
+        let mut inner = Inner::new();
+        inner.expire_at = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        inner.values = values;
+        #[cfg(feature = "name")]
+        if let Some(name) = name {
+            inner.name = name;
+        }
+
+        Self::from(inner)
+    }
+
+    /// return the time left before the deadline, measured on tokio's clock.
+    pub(crate) fn remaining(&self) -> Option<time::Duration> {
+        self.inner.expire_at
+            .map(|expire_at| expire_at.saturating_duration_since(Instant::now()))
+    }
+
+    /// return the [`tracing::Span`] of this context.
+    ///
+    /// The span is a child of the span of the parent context, or of the current span for
+    /// a root context. It records the `name` and the time left before the `deadline` at creation,
+    /// and the `outcome`: `cancelled`, `timeout` when the timeout is observed,
+    /// or `completed` when it is dropped otherwise.
+    #[cfg(feature = "tracing")]
+    pub fn span(&self) -> &tracing::Span {
+        &self.inner.span
+    }
+
+    /// take a [`Snapshot`] of this context and its live descendants.
+    pub fn snapshot(&self) -> Snapshot {
+        // upgrade the childs, then release the lock before walking them.
+        let mut childs: Vec<_> = self.inner.childs().values()
+            .filter_map(Weak::upgrade)
+            .map(|inner| Self { inner })
+            .collect();
+
+        // ids increase with creation, list the childs in creation order.
+        childs.sort_by_key(|child| child.inner.id);
+
+        let now = Instant::now();
+
+        #[cfg(feature = "name")]
+        let name = Some(self.inner.name.to_string());
+        #[cfg(not(feature = "name"))]
+        let name = None;
+
+        Snapshot {
+            name,
+            age: now.saturating_duration_since(self.inner.created_at),
+            remaining: self.inner.expire_at.map(|expire_at| expire_at.saturating_duration_since(now)),
+            cancelled: self.try_is_cancelled(),
+            timeout: self.try_is_timeout(),
+            childs: childs.iter().map(Self::snapshot).collect(),
+        }
+    }
+
+    /// return the values carried by this context.
+    pub(crate) fn values(&self) -> Option<&Values> {
+        self.inner.values.as_deref()
+    }
+
+    /// return the name of this context, without awaiting.
+    #[cfg(feature = "name")]
+    pub(crate) fn name_now(&self) -> Name {
+        self.inner.name.clone()
+    }
+
+    /// Create a root timer with no time duration limit, named `name`.
+    ///
+    /// The name can come from upstream, such as an `X-Request-Id` header given to [`Name::from_id`].
+    #[cfg(feature = "name")]
+    pub fn with_name(name: Name) -> Self {
+        Self::from(Inner::build(
+            name,
+            None,
+            #[cfg(feature = "tracing")]
+            None,
+        ))
+    }
+
+    /// Create a root timer with no time duration limit, whose [`Name`] and the names
+    /// of all its descendants are created by `generator`.
+    #[cfg(feature = "name")]
+    pub fn with_name_generator(generator: NameGenerator) -> Self {
+        Self::from(Inner::build(
+            generator.generate(),
+            Some(generator),
+            #[cfg(feature = "tracing")]
+            None,
+        ))
+    }
+
+    /// Create a root timer with no time duration limit, whose [`Name`] is labeled `label`.
+    #[cfg(feature = "name")]
+    pub fn named(label: impl Into<Arc<str>>) -> Self {
+        let mut inner = Inner::new();
+        inner.name.set_label(label.into());
+
+        Self::from(inner)
+    }
+
+    /// Create a root timer with a maximum execution duration, whose [`Name`] is labeled `label`.
+    #[cfg(feature = "name")]
+    pub fn named_with_timeout(label: impl Into<Arc<str>>, timeout: time::Duration) -> Self {
+        let mut inner = Inner::new();
+        inner.name.set_label(label.into());
+        inner.expire_at = Some(Instant::now() + timeout);
 
         Self::from(inner)
     }
@@ -213,61 +453,358 @@ impl Timer {
         Self::with_timeout(time::Duration::from_millis(millis))
     }
 
-    async fn cancel_receiver(&self) -> sync::broadcast::Receiver<()> {
-        self.inner.read().await.cancelled_receiver.resubscribe()
+    /// spawn a new child context, which carries `value` under the key `K`.
+    ///
+    /// The value is visible to the child and all its descendants, through [`Context::value`].
+    /// If an ancestor already carries a value for `K`, the child sees the new one.
+    pub async fn with_value<K: Key>(&self, value: K::Value) -> Self {
+        let mut child = Inner::child_of(&self.inner);
+        child.expire_at = self.inner.expire_at;
+        child.values = Some(Arc::new(Values::new::<K>(value, self.inner.values.clone())));
+
+        self.attach(child)
+    }
+
+    /// return a [`CancelGuard`], which cancels this context when dropped.
+    pub fn cancel_guard(&self) -> CancelGuard {
+        CancelGuard::new(self.clone())
+    }
+
+    /// spawn a new child context, with a [`CancelGuard`] which cancels the child when dropped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use context_async::{Context, Timer};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ctx = Timer::background();
+    /// let (child, guard) = ctx.spawn_scoped().await;
+    ///
+    /// drop(guard);
+    /// assert!(child.is_cancelled().await);
+    /// assert!(!ctx.is_cancelled().await);
+    /// # });
+    /// ```
+    pub async fn spawn_scoped(&self) -> (Self, CancelGuard) {
+        let child = self.spawn().await;
+        let guard = child.cancel_guard();
+
+        (child, guard)
+    }
+
+    /// spawn a new child context, whose [`Name`] is labeled `label`.
+    ///
+    /// Same as [`Context::spawn`], the child's name is a path below the name of `self`.
+    #[cfg(feature = "name")]
+    pub async fn spawn_named(&self, label: impl Into<Arc<str>> + Send) -> Self {
+        let mut child = Inner::child_of(&self.inner);
+        child.name.set_label(label.into());
+
+        self.spawn_inner(child, None)
+    }
+
+    /// spawn a new child context with a new timeout, whose [`Name`] is labeled `label`.
+    ///
+    /// Same as [`Context::spawn_with_timeout`], the child's name is a path below the name of `self`.
+    #[cfg(feature = "name")]
+    pub async fn spawn_named_with_timeout(&self, label: impl Into<Arc<str>> + Send, timeout: time::Duration) -> Self {
+        let mut child = Inner::child_of(&self.inner);
+        child.name.set_label(label.into());
+
+        self.spawn_inner(child, Some(timeout))
+    }
+
+    /// make the deadline of this context fire actively, and return it.
+    ///
+    /// By default, a deadline is only observed when the context is queried or awaited.
+    /// Once active, a background task cancels the context with [`Error::ContextTimeout`]
+    /// as soon as the deadline passes: waiters are woken, [`Context::is_cancelled`] becomes
+    /// `true`, and the childs are cancelled. Childs spawned later are active too.
+    ///
+    /// # Panics
+    /// Panics if called outside of a tokio runtime, when the context has a deadline.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time;
+    /// use context_async::{Context, Error, Timer};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ctx = Timer::in_milliseconds(100).fire_on_deadline();
+    /// let child = ctx.spawn().await;
+    ///
+    /// tokio::time::sleep(time::Duration::from_millis(200)).await;
+    /// assert!(child.try_is_cancelled());
+    /// assert_eq!(child.try_error(), Some(Error::ContextTimeout));
+    /// # });
+    /// ```
+    pub fn fire_on_deadline(self) -> Self {
+        if !self.inner.active.swap(true, Ordering::AcqRel) {
+            self.watch_deadline();
+        }
+
+        self
+    }
+
+    /// spawn a task which cancels this context with [`Error::ContextTimeout`] at its deadline.
+    ///
+    /// The task holds the context weakly, and stops early if the context is cancelled or dropped.
+    fn watch_deadline(&self) {
+        let Some(expire_at) = self.inner.expire_at else {
+            return;
+        };
+
+        let mut receiver = self.cancel_receiver();
+        if self.try_is_cancelled() {
+            return;
+        }
+
+        let inner = Arc::downgrade(&self.inner);
+        tokio::spawn(async move {
+            let mut sleep = pin!(tokio::time::sleep_until(expire_at));
+            let mut cancelled = pin!(receiver.recv());
+
+            let expired = poll_fn(|cx| {
+                if sleep.as_mut().poll(cx).is_ready() {
+                    Poll::Ready(true)
+                } else if cancelled.as_mut().poll(cx).is_ready() {
+                    Poll::Ready(false)
+                } else {
+                    Poll::Pending
+                }
+            }).await;
+
+            if let Some(inner) = inner.upgrade().filter(|_| expired) {
+                let timer = Self { inner };
+                timer.cancel_with_error(Error::ContextTimeout);
+                timer.observe_timeout();
+            }
+        });
+    }
+
+    /// return `true` if the deadline of this context has passed.
+    fn expired(&self) -> bool {
+        self.inner.expire_at
+            .is_some_and(|expire_at| expire_at <= Instant::now())
+    }
+
+    /// record the timeout of this context, once, when it is observed by a [`Done`],
+    /// [`Context::handle`], [`Context::is_timeout`], [`Context::error`]
+    /// or the watcher of [`Timer::fire_on_deadline`].
+    fn observe_timeout(&self) {
+        if !self.inner.timeout_observed.swap(true, Ordering::Relaxed) {
+            #[cfg(feature = "tracing")]
+            self.inner.span.record("outcome", "timeout");
+
+            #[cfg(feature = "metrics")]
+            crate::metrics::timed_out(&self.inner.operation);
+        }
+    }
+
+    fn cancel_receiver(&self) -> sync::broadcast::Receiver<Error> {
+        self.inner.cancelled_sender.subscribe()
+    }
+
+    /// spawn a new child context, with an optional new timeout.
+    ///
+    /// This is the synchronous version of [`Context::spawn`] and [`Context::spawn_with_timeout`].
+    pub(crate) fn spawn_child(&self, timeout: Option<time::Duration>) -> Self {
+        self.spawn_inner(Inner::child_of(&self.inner), timeout)
+    }
+
+    fn spawn_inner(&self, mut child: Inner, timeout: Option<time::Duration>) -> Self {
+        let child_expire_at = timeout.map(|timeout| Instant::now() + timeout);
+        let child_expire_at = match (self.inner.expire_at, child_expire_at) {
+            (Some(expire_at), Some(child_expire_at)) if expire_at < child_expire_at => Some(expire_at),
+            (expire_at, None) => expire_at,
+            (_, child_expire_at) => child_expire_at,
+        };
+
+        child.expire_at = child_expire_at;
+        child.values = self.inner.values.clone();
+
+        #[cfg(feature = "tracing")]
+        {
+            #[cfg(feature = "name")]
+            tracing::trace!(context_spawn=%self.inner.name, with_timeout=?timeout, child=%child.name, expire_at=?child.expire_at);
+            #[cfg(not(feature = "name"))]
+            tracing::trace!(context_spawn="", with_timeout=?timeout, expire_at=?child.expire_at)
+        }
+
+        self.attach(child)
+    }
+
+    /// register `child` as a child of `self`.
+    ///
+    /// The child holds its parent, but the parent only holds a weak reference to the child,
+    /// keyed by the id of the child, which removes its own entry when dropped.
+    fn attach(&self, mut child: Inner) -> Self {
+        child.parent = Some(self.clone());
+        let child = Self::from(child);
+
+        self.inner.childs().insert(child.inner.id, Arc::downgrade(&child.inner));
+
+        // `self` may be cancelled before the child is registered.
+        if let Some(err) = self.inner.cancelled.get() {
+            child.cancel_with_error(err.clone());
+        }
+
+        // a child with the deadline of `self` is cancelled with it,
+        // only an earlier deadline needs its own watcher.
+        if self.inner.active.load(Ordering::Acquire) {
+            child.inner.active.store(true, Ordering::Release);
+            if child.inner.expire_at != self.inner.expire_at {
+                child.watch_deadline();
+            }
+        }
+
+        child
+    }
+
+    /// cancel this context with `error`, then cancel all its childs with the same error.
+    pub(crate) fn cancel_with_error(&self, error: Error) {
+        if self.inner.cancelled.set(error.clone()).is_err() {
+            return;
+        }
+
+        #[cfg(feature = "tracing")]
+        {
+            #[cfg(feature = "name")]
+            tracing::trace!(context_cancel=%self.inner.name, error=%error);
+            #[cfg(not(feature = "name"))]
+            tracing::trace!(context_cancel="", error=%error);
+
+            self.inner.span.record("outcome", if error.is_timeout() { "timeout" } else { "cancelled" });
+        }
+
+        // a timeout is counted when it is observed, see `observe_timeout`.
+        #[cfg(feature = "metrics")]
+        if !error.is_timeout() {
+            crate::metrics::cancelled(&self.inner.operation);
+        }
+
+        let _ = self.inner.cancelled_sender.send(error.clone());
+
+        let childs: Vec<_> = self.inner.childs().values()
+            .filter_map(Weak::upgrade)
+            .map(|inner| Self { inner })
+            .collect();
+
+        for child in childs {
+            child.cancel_with_error(error.clone());
+        }
     }
 }
 
-struct Task<Output, Fut, CErr, CFut>
-where
-    Fut: Future<Output = Output> + Send,
-    CErr: ::std::error::Error,
-    CFut: Future<Output = Result<(), CErr>> + Send,
-{
-    fut: Pin<Box<Fut>>,
+/// A future that resolves with an [`Error`] when its context is cancelled or timeout.
+///
+/// It is returned by [`Context::done`], and never resolves for a context
+/// which has no deadline and is never cancelled.
+/// It can be created outside of a tokio runtime, the deadline is only watched once it is polled.
+///
+/// # Examples
+/// ```rust
+/// use std::time;
+/// use context_async::{Context, Error, Timer};
+///
+/// # tokio_test::block_on(async {
+/// let ctx = Timer::in_milliseconds(100);
+///
+/// let err = tokio::select! {
+///     _ = tokio::time::sleep(time::Duration::from_secs(10)) => unreachable!(),
+///     err = ctx.done() => err,
+/// };
+///
+/// assert_eq!(err, Error::ContextTimeout);
+/// # });
+/// ```
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Done {
+    // keep the context alive, so that the cancel sender is never dropped.
+    timer: Timer,
+    error: Option<Error>,
+    // created on first poll, so that a `Done` is created outside of a tokio runtime.
     sleep: Option<Pin<Box<Sleep>>>,
-    cancel_receiver: Pin<Box<CFut>>,
+    cancel_receiver: Pin<Box<dyn Future<Output = Result<Error, RecvError>> + Send>>,
+}
+
+impl Done {
+    fn new(timer: &Timer) -> Self {
+        // subscribe before checking, so that a concurrent cancel is never missed.
+        let mut cancel_receiver = timer.cancel_receiver();
+        let error = timer.try_error();
+
+        Self {
+            timer: timer.clone(),
+            error,
+            sleep: None,
+            cancel_receiver: Box::pin(async move { cancel_receiver.recv().await }),
+        }
+    }
+}
+
+impl Debug for Done {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Done")
+            .field("error", &self.error)
+            .field("sleep", &self.sleep)
+            .finish_non_exhaustive()
+    }
 }
 
-impl<Output, Fut, CErr, CFut> Future for Task<Output, Fut, CErr, CFut>
-where
-    Fut: Future<Output = Output> + Send,
-    CErr: std::error::Error,
-    CFut: Future<Output = Result<(), CErr>> + Send,
-{
-    type Output = crate::Result<Output>;
+impl Future for Done {
+    type Output = Error;
 
     fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
 
-        if let Some(sleep) = this.sleep.as_mut() {
-            if pin!(sleep).poll(cx).is_ready() {
-                return Poll::Ready(Err(Error::ContextTimeout));
-            }
+        // once resolved, the error is kept, and the sleep and receiver are never polled again.
+        // a timeout resolved at creation is recorded by `try_error`.
+        if let Some(err) = this.error.clone() {
+            return Poll::Ready(err);
+        }
+
+        if let (None, Some(expire_at)) = (&this.sleep, this.timer.inner.expire_at) {
+            this.sleep = Some(Box::pin(tokio::time::sleep_until(expire_at)));
         }
 
-        if let Poll::Ready(cancel_result) = pin!(&mut this.cancel_receiver).poll(cx) {
-            if let Err(e) = cancel_result {
+        let err = if this.sleep.as_mut().is_some_and(|sleep| sleep.as_mut().poll(cx).is_ready()) {
+            Error::ContextTimeout
+        } else if let Poll::Ready(cancel_result) = this.cancel_receiver.as_mut().poll(cx) {
+            cancel_result.unwrap_or_else(|e| {
                 error!("BUG: error when RecvError: {:?}", e);
-            }
+                Error::ContextCancelled
+            })
+        } else {
+            return Poll::Pending;
+        };
 
-            return Poll::Ready(Err(Error::ContextCancelled));
+        if err.is_timeout() {
+            this.timer.observe_timeout();
         }
 
-        this.fut.as_mut().poll(cx)
-            .map(|r| Ok(r))
+        this.error = Some(err.clone());
+        Poll::Ready(err)
     }
 }
 
-#[cfg(feature = "actix-web-from-request")]
-impl actix_web::FromRequest for Timer {
-    type Error = actix_web::Error;
-    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+struct Task<Fut: Future + Send> {
+    done: Done,
+    fut: Pin<Box<Fut>>,
+}
+
+impl<Fut: Future + Send> Future for Task<Fut> {
+    type Output = crate::Result<Fut::Output>;
 
-    fn from_request(_: &actix_web::HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
-        Box::pin(async {
-            Ok(Timer::background())
-        })
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if let Poll::Ready(err) = Pin::new(&mut this.done).poll(cx) {
+            return Poll::Ready(Err(err));
+        }
+
+        this.fut.as_mut().poll(cx)
+            .map(Ok)
     }
 }
 
@@ -276,10 +813,30 @@ impl Drop for Inner {
         #[cfg(feature = "tracing")]
         {
             #[cfg(feature = "name")]
-            tracing::trace!(context_drop=self.name.as_u64(), cancelled=self.cancelled, timeout=?self.expire_at);
+            tracing::trace!(context_drop=%self.name, cancelled=self.cancelled.get().is_some(), timeout=?self.expire_at);
 
             #[cfg(not(feature = "name"))]
-            tracing::trace!(context_drop="", cancelled=self.cancelled, timeout=?self.expire_at);
+            tracing::trace!(context_drop="", cancelled=self.cancelled.get().is_some(), timeout=?self.expire_at);
+
+            // an observed timeout is recorded by `observe_timeout`.
+            if self.cancelled.get().is_none() && !self.timeout_observed.load(Ordering::Relaxed) {
+                self.span.record("outcome", "completed");
+            }
+        }
+
+        // detach from the parent.
+        if let Some(parent) = self.parent.take() {
+            let mut childs = parent.inner.childs();
+            childs.remove(&self.id);
+
+            #[cfg(feature = "tracing")]
+            {
+                #[cfg(feature = "name")]
+                tracing::trace!(context_detach=%self.name, parent=%parent.inner.name, childs=childs.len());
+
+                #[cfg(not(feature = "name"))]
+                tracing::trace!(context_detach="", childs=childs.len());
+            }
         }
     }
 }
@@ -0,0 +1,104 @@
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+use crate::{Context, Error, Timer};
+use crate::header::{encode_grpc_timeout, parse_grpc_timeout};
+
+/// The standard gRPC header carrying the timeout of a call.
+const GRPC_TIMEOUT: &str = "grpc-timeout";
+
+/// Turn an [`Error`] into a [`Status`]: `DEADLINE_EXCEEDED` for a timeout,
+/// or `CANCELLED` for a cancellation.
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        if value.is_timeout() {
+            Status::deadline_exceeded(value.to_string())
+        } else {
+            Status::cancelled(value.to_string())
+        }
+    }
+}
+
+/// write the remaining time of `ctx` into the `grpc-timeout` header of `request`.
+///
+/// An existing `grpc-timeout` is kept if it is shorter. It fails with the [`Status`]
+/// of the [`Error`] if `ctx` is already cancelled or timeout.
+pub fn set_grpc_timeout<Ctx: Context, T>(ctx: &Ctx, request: &mut Request<T>) -> Result<(), Status> {
+    if let Some(err) = ctx.try_error() {
+        return Err(err.into());
+    }
+
+    let Some(remaining) = ctx.timer().remaining() else {
+        return Ok(());
+    };
+
+    let existing = request.metadata().get(GRPC_TIMEOUT)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_grpc_timeout);
+
+    if existing.is_none_or(|existing| remaining < existing) {
+        let value = encode_grpc_timeout(remaining).parse()
+            .expect("BUG: grpc-timeout is always ascii");
+        request.metadata_mut().insert(GRPC_TIMEOUT, value);
+    }
+
+    Ok(())
+}
+
+/// The [`GrpcTimeoutInterceptor`] propagates the deadline of a [`Timer`]
+/// to the gRPC calls of a client, through the `grpc-timeout` header.
+///
+/// # Examples
+/// ```rust
+/// use tonic::service::Interceptor;
+/// use context_async::{GrpcTimeoutInterceptor, Timer};
+///
+/// let ctx = Timer::in_seconds(5);
+/// let mut interceptor = GrpcTimeoutInterceptor::new(&ctx);
+///
+/// let request = interceptor.call(tonic::Request::new(())).unwrap();
+/// assert!(request.metadata().get("grpc-timeout").is_some());
+///
+/// // with a generated client:
+/// // let client = GreeterClient::with_interceptor(channel, GrpcTimeoutInterceptor::new(&ctx));
+/// ```
+#[derive(Debug, Clone)]
+pub struct GrpcTimeoutInterceptor {
+    timer: Timer,
+}
+
+impl GrpcTimeoutInterceptor {
+    pub fn new<Ctx: Context>(ctx: Ctx) -> Self {
+        Self {
+            timer: ctx.timer(),
+        }
+    }
+}
+
+impl Interceptor for GrpcTimeoutInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        set_grpc_timeout(&self.timer, &mut request)?;
+        Ok(request)
+    }
+}
+
+impl Timer {
+    /// return the context of a gRPC request on the server side.
+    ///
+    /// It returns the [`Timer`] installed in the request extensions by a layer, if any.
+    /// Otherwise, it creates a new root [`Timer`], whose deadline is read from
+    /// the `grpc-timeout` header of the request.
+    pub fn from_grpc_request<T>(request: &Request<T>) -> Self {
+        if let Some(timer) = request.extensions().get::<Timer>() {
+            return timer.clone();
+        }
+
+        let timeout = request.metadata().get(GRPC_TIMEOUT)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_grpc_timeout);
+
+        match timeout {
+            Some(timeout) => Timer::with_timeout(timeout),
+            None => Timer::background(),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use std::time;
+use http::HeaderName;
+use tower_layer::Layer;
+use tower_service::Service;
+use crate::{CancelGuard, Context, Done, Timer};
+use crate::header::{request_timer, REQUEST_TIMEOUT};
+
+/// The error type of [`ContextService`].
+///
+/// It is either the error of the inner service, or a [`crate::Error`] when the
+/// request context is cancelled or timeout.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The [`ContextLayer`] attaches a [`Timer`] to every request, in its `http::Extensions`,
+/// and runs the inner service under it.
+///
+/// When the request context is cancelled or timeout, the service fails with the
+/// [`crate::Error`], boxed in [`BoxError`]. The request context is cancelled if
+/// the response future is dropped before it completes, such as when the client disconnects.
+/// Once the response is returned, the context lives on, so that a streaming
+/// body can still use it until its deadline.
+///
+/// # Examples
+/// ```rust
+/// use std::time;
+/// use tower::{Layer, ServiceExt};
+/// use context_async::{Context, ContextLayer, Timer};
+///
+/// # tokio_test::block_on(async {
+/// let service = tower::service_fn(|req: http::Request<()>| async move {
+///     let ctx = req.extensions().get::<Timer>().unwrap();
+///     Ok::<_, std::convert::Infallible>(ctx.deadline().await.is_some())
+/// });
+///
+/// let service = ContextLayer::new()
+///     .timeout(time::Duration::from_secs(5))
+///     .layer(service);
+///
+/// let has_deadline = service.oneshot(http::Request::new(())).await.unwrap();
+/// assert!(has_deadline);
+/// # });
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContextLayer {
+    timeout: Option<time::Duration>,
+    header: HeaderName,
+    root: Option<Timer>,
+}
+
+impl Default for ContextLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContextLayer {
+    /// create a layer, which reads the timeout of a request from the `X-Request-Timeout` header,
+    /// without default timeout.
+    pub fn new() -> Self {
+        Self {
+            timeout: None,
+            header: HeaderName::from_static(REQUEST_TIMEOUT),
+            root: None,
+        }
+    }
+
+    /// set the timeout of a request without a valid timeout header.
+    pub fn timeout(mut self, timeout: time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// read the timeout of a request from `header`, falling back to [`Self::timeout`].
+    ///
+    /// Its value is either a plain number of milliseconds, or a `grpc-timeout` value such as `100m`.
+    pub fn header(mut self, header: HeaderName) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// spawn every request context from `root`, so that cancelling `root`
+    /// stops every in-flight request.
+    pub fn root(mut self, root: Timer) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    fn timer<B>(&self, req: &http::Request<B>) -> Timer {
+        let header_value = req.headers().get(&self.header)
+            .and_then(|value| value.to_str().ok());
+
+        request_timer(header_value, self.timeout, self.root.as_ref())
+    }
+}
+
+impl<S> Layer<S> for ContextLayer {
+    type Service = ContextService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ContextService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// The service created by [`ContextLayer`].
+#[derive(Debug, Clone)]
+pub struct ContextService<S> {
+    inner: S,
+    layer: ContextLayer,
+}
+
+impl<S, B> Service<http::Request<B>> for ContextService<S>
+where
+    S: Service<http::Request<B>>,
+    S::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = ContextFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        let timer = self.layer.timer(&req);
+        req.extensions_mut().insert(timer.clone());
+
+        ContextFuture {
+            done: timer.done(),
+            fut: Box::pin(self.inner.call(req)),
+            guard: Some(timer.cancel_guard()),
+        }
+    }
+}
+
+/// The response future of [`ContextService`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ContextFuture<F> {
+    done: Done,
+    fut: Pin<Box<F>>,
+    // cancels the context if the future is dropped before it completes.
+    guard: Option<CancelGuard>,
+}
+
+impl<F, T, E> Future for ContextFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<BoxError>,
+{
+    type Output = Result<T, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if let Poll::Ready(err) = Pin::new(&mut this.done).poll(cx) {
+            if let Some(guard) = this.guard.take() {
+                guard.disarm();
+            }
+
+            return Poll::Ready(Err(Box::new(err)));
+        }
+
+        let output = std::task::ready!(this.fut.as_mut().poll(cx));
+        if let Some(guard) = this.guard.take() {
+            guard.disarm();
+        }
+
+        Poll::Ready(output.map_err(Into::into))
+    }
+}
+
+impl<F> Debug for ContextFuture<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContextFuture")
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,74 @@
+use std::any::{Any, TypeId};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// The [`Key`] trait defines a typed key for values carried by a [`crate::Timer`].
+///
+/// Each key is a distinct type, so values stored by different libraries
+/// never clash, even if they carry the same value type.
+///
+/// # Examples
+/// ```
+/// use context_async::{Context, Key, Timer};
+///
+/// struct RequestId;
+///
+/// impl Key for RequestId {
+///     type Value = u64;
+/// }
+///
+/// # tokio_test::block_on(async {
+/// let ctx = Timer::background().with_value::<RequestId>(42).await;
+/// let child = ctx.spawn().await;
+///
+/// assert_eq!(child.value::<RequestId>().await.as_deref(), Some(&42));
+/// # });
+/// ```
+pub trait Key: 'static {
+    type Value: Send + Sync + 'static;
+}
+
+/// A node in the chain of values, linked to the values of the parent context.
+pub(crate) struct Values {
+    key: TypeId,
+    value: Arc<dyn Any + Send + Sync>,
+    parent: Option<Arc<Values>>,
+}
+
+impl Values {
+    pub(crate) fn new<K: Key>(value: K::Value, parent: Option<Arc<Values>>) -> Self {
+        Self {
+            key: TypeId::of::<K>(),
+            value: Arc::new(value),
+            parent,
+        }
+    }
+
+    /// look up the value of `K`, from this node up to the root.
+    pub(crate) fn get<K: Key>(&self) -> Option<Arc<K::Value>> {
+        let key = TypeId::of::<K>();
+        let mut node = Some(self);
+
+        while let Some(current) = node {
+            if current.key == key {
+                return current.value.clone().downcast().ok();
+            }
+            node = current.parent.as_deref();
+        }
+
+        None
+    }
+}
+
+impl Debug for Values {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut len = 0;
+        let mut node = Some(self);
+        while let Some(current) = node {
+            len += 1;
+            node = current.parent.as_deref();
+        }
+
+        f.debug_struct("Values").field("len", &len).finish()
+    }
+}
@@ -0,0 +1,511 @@
+//! Propagate a [`Context`] across process boundaries with [`WireContext`].
+//!
+//! The [`WireContext`] and [`Baggage`] are also re-exported at the crate root.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{self, SystemTime, UNIX_EPOCH};
+use crate::{Context, Error, Key, Timer};
+use crate::value::Values;
+
+/// The [`Baggage`] key carries string values, which are propagated
+/// across process boundaries by [`WireContext`].
+///
+/// # Examples
+/// ```rust
+/// use std::collections::BTreeMap;
+/// use context_async::{Baggage, Context, Timer, WireContext};
+///
+/// # tokio_test::block_on(async {
+/// let baggage = BTreeMap::from([(String::from("tenant"), String::from("acme"))]);
+/// let ctx = Timer::in_seconds(5).with_value::<Baggage>(baggage).await;
+///
+/// let encoded = WireContext::new(&ctx).to_string();
+/// let decoded: WireContext = encoded.parse().unwrap();
+/// let remote = decoded.into_timer();
+///
+/// assert_eq!(remote.value::<Baggage>().await.unwrap()["tenant"], "acme");
+/// assert!(remote.deadline().await.is_some());
+/// # });
+/// ```
+pub struct Baggage;
+
+impl Key for Baggage {
+    type Value = BTreeMap<String, String>;
+}
+
+/// The header carrying the remaining time of the context, in milliseconds.
+pub const TIMEOUT_HEADER: &str = "x-context-timeout";
+/// The header carrying the wall-clock deadline of the context, in milliseconds since the unix epoch.
+pub const DEADLINE_HEADER: &str = "x-context-deadline";
+/// The header carrying the name of the context.
+pub const NAME_HEADER: &str = "x-context-name";
+/// The header set to `1` when the context is cancelled.
+pub const CANCELLED_HEADER: &str = "x-context-cancelled";
+/// The header carrying the [`Baggage`] of the context, as `key=value` pairs separated by `,`.
+pub const BAGGAGE_HEADER: &str = "x-context-baggage";
+
+const VERSION: u8 = 1;
+const STRING_VERSION: &str = "v1";
+
+const FLAG_TIMEOUT: u8 = 1;
+const FLAG_DEADLINE: u8 = 1 << 1;
+const FLAG_NAME: u8 = 1 << 2;
+const FLAG_CANCELLED: u8 = 1 << 3;
+const FLAG_BAGGAGE: u8 = 1 << 4;
+const FLAGS: u8 = FLAG_TIMEOUT | FLAG_DEADLINE | FLAG_NAME | FLAG_CANCELLED | FLAG_BAGGAGE;
+
+/// The [`WireContext`] is the propagatable state of a [`Context`]: its deadline, name,
+/// cancellation and [`Baggage`], to send it across process boundaries.
+///
+/// It is encoded as HTTP headers, as a compact binary blob, or as a string,
+/// for example for an environment variable. Durations are encoded in milliseconds.
+/// Decoding it with [`WireContext::into_timer`] gives a new root [`Timer`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WireContext {
+    /// the remaining time of the context.
+    pub timeout: Option<time::Duration>,
+    /// the wall-clock deadline of the context.
+    pub deadline: Option<SystemTime>,
+    /// the name of the context.
+    pub name: Option<String>,
+    /// whether the context is cancelled.
+    pub cancelled: bool,
+    /// the [`Baggage`] of the context.
+    pub baggage: BTreeMap<String, String>,
+}
+
+/// The error of decoding a malformed [`WireContext`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DecodeError(&'static str);
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "malformed context: {}", self.0)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl WireContext {
+    /// capture the propagatable state of `ctx`.
+    pub fn new<Ctx: Context>(ctx: &Ctx) -> Self {
+        let timer = ctx.timer();
+        let timeout = timer.remaining();
+
+        #[cfg(feature = "name")]
+        let name = Some(timer.name_now().to_string());
+        #[cfg(not(feature = "name"))]
+        let name = None;
+
+        Self {
+            timeout,
+            deadline: timeout.and_then(|timeout| SystemTime::now().checked_add(timeout)),
+            name,
+            cancelled: timer.try_is_cancelled(),
+            baggage: timer.values()
+                .and_then(|values| values.get::<Baggage>())
+                .map(|baggage| (*baggage).clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// create a new root [`Timer`] with this state.
+    ///
+    /// Its deadline is the earlier of [`Self::timeout`] from now and [`Self::deadline`].
+    /// With the `name` feature, it is named [`Self::name`] when it parses as a `Name`.
+    pub fn into_timer(self) -> Timer {
+        let from_deadline = self.deadline
+            .map(|deadline| deadline.duration_since(SystemTime::now()).unwrap_or_default());
+        let timeout = match (self.timeout, from_deadline) {
+            (Some(timeout), Some(from_deadline)) => Some(timeout.min(from_deadline)),
+            (timeout, from_deadline) => timeout.or(from_deadline),
+        };
+
+        let values = (!self.baggage.is_empty())
+            .then(|| Arc::new(Values::new::<Baggage>(self.baggage, None)));
+
+        let timer = Timer::from_wire(
+            timeout,
+            values,
+            #[cfg(feature = "name")]
+            self.name.and_then(|name| name.parse::<crate::Name>().ok()),
+        );
+
+        if self.cancelled {
+            timer.cancel_with_error(Error::ContextCancelled);
+        }
+
+        timer
+    }
+
+    /// encode this state as HTTP headers.
+    pub fn to_headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+
+        if let Some(timeout) = self.timeout {
+            headers.push((TIMEOUT_HEADER, timeout.as_millis().to_string()));
+        }
+        if let Some(deadline) = self.deadline {
+            headers.push((DEADLINE_HEADER, unix_millis(deadline).to_string()));
+        }
+        if let Some(name) = &self.name {
+            headers.push((NAME_HEADER, percent_encode(name)));
+        }
+        if self.cancelled {
+            headers.push((CANCELLED_HEADER, String::from("1")));
+        }
+        if !self.baggage.is_empty() {
+            headers.push((BAGGAGE_HEADER, encode_baggage(&self.baggage)));
+        }
+
+        headers
+    }
+
+    /// decode the state from HTTP headers. The other headers are ignored.
+    pub fn from_headers<'a, I>(headers: I) -> Result<Self, DecodeError>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let mut wire = Self::default();
+        let mut seen = 0u8;
+
+        for (name, value) in headers {
+            let flag = match name.to_ascii_lowercase().as_str() {
+                TIMEOUT_HEADER => {
+                    wire.timeout = Some(time::Duration::from_millis(parse_u64(value)?));
+                    FLAG_TIMEOUT
+                },
+                DEADLINE_HEADER => {
+                    wire.deadline = Some(from_unix_millis(parse_u64(value)?)?);
+                    FLAG_DEADLINE
+                },
+                NAME_HEADER => {
+                    wire.name = Some(percent_decode(value)?);
+                    FLAG_NAME
+                },
+                CANCELLED_HEADER => {
+                    wire.cancelled = match value.trim() {
+                        "1" => true,
+                        "0" => false,
+                        _ => return Err(DecodeError("invalid cancelled flag")),
+                    };
+                    FLAG_CANCELLED
+                },
+                BAGGAGE_HEADER => {
+                    wire.baggage = decode_baggage(value)?;
+                    FLAG_BAGGAGE
+                },
+                _ => continue,
+            };
+
+            if seen & flag != 0 {
+                return Err(DecodeError("duplicate header"));
+            }
+            seen |= flag;
+        }
+
+        Ok(wire)
+    }
+
+    /// encode this state as a compact binary blob.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut flags = 0;
+        let mut body = Vec::new();
+
+        if let Some(timeout) = self.timeout {
+            flags |= FLAG_TIMEOUT;
+            write_varint(&mut body, u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX));
+        }
+        if let Some(deadline) = self.deadline {
+            flags |= FLAG_DEADLINE;
+            write_varint(&mut body, unix_millis(deadline));
+        }
+        if let Some(name) = &self.name {
+            flags |= FLAG_NAME;
+            write_bytes(&mut body, name.as_bytes());
+        }
+        if self.cancelled {
+            flags |= FLAG_CANCELLED;
+        }
+        if !self.baggage.is_empty() {
+            flags |= FLAG_BAGGAGE;
+            write_varint(&mut body, self.baggage.len() as u64);
+            for (key, value) in &self.baggage {
+                write_bytes(&mut body, key.as_bytes());
+                write_bytes(&mut body, value.as_bytes());
+            }
+        }
+
+        let mut bytes = Vec::with_capacity(body.len() + 2);
+        bytes.push(VERSION);
+        bytes.push(flags);
+        bytes.extend(body);
+        bytes
+    }
+
+    /// decode the state from a binary blob, created by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader(bytes);
+
+        if reader.byte()? != VERSION {
+            return Err(DecodeError("unsupported version"));
+        }
+
+        let flags = reader.byte()?;
+        if flags & !FLAGS != 0 {
+            return Err(DecodeError("unknown flags"));
+        }
+
+        let mut wire = Self::default();
+
+        if flags & FLAG_TIMEOUT != 0 {
+            wire.timeout = Some(time::Duration::from_millis(reader.varint()?));
+        }
+        if flags & FLAG_DEADLINE != 0 {
+            wire.deadline = Some(from_unix_millis(reader.varint()?)?);
+        }
+        if flags & FLAG_NAME != 0 {
+            wire.name = Some(reader.string()?);
+        }
+        wire.cancelled = flags & FLAG_CANCELLED != 0;
+        if flags & FLAG_BAGGAGE != 0 {
+            let len = reader.varint()?;
+            for _ in 0..len {
+                let key = reader.string()?;
+                let value = reader.string()?;
+                if wire.baggage.insert(key, value).is_some() {
+                    return Err(DecodeError("duplicate baggage key"));
+                }
+            }
+        }
+
+        if !reader.0.is_empty() {
+            return Err(DecodeError("trailing bytes"));
+        }
+
+        Ok(wire)
+    }
+}
+
+/// Encode the state as a string, such as `v1;timeout=1500;cancelled`.
+impl Display for WireContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(STRING_VERSION)?;
+
+        if let Some(timeout) = self.timeout {
+            write!(f, ";timeout={}", timeout.as_millis())?;
+        }
+        if let Some(deadline) = self.deadline {
+            write!(f, ";deadline={}", unix_millis(deadline))?;
+        }
+        if let Some(name) = &self.name {
+            write!(f, ";name={}", percent_encode(name))?;
+        }
+        if self.cancelled {
+            f.write_str(";cancelled")?;
+        }
+        if !self.baggage.is_empty() {
+            write!(f, ";baggage={}", encode_baggage(&self.baggage))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Decode the state from a string, created by its [`Display`] implementation.
+/// Unknown fields are ignored.
+impl FromStr for WireContext {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.trim().split(';');
+        if fields.next() != Some(STRING_VERSION) {
+            return Err(DecodeError("unsupported version"));
+        }
+
+        let mut wire = Self::default();
+        let mut seen = 0u8;
+
+        for field in fields {
+            let (key, value) = match field.split_once('=') {
+                Some((key, value)) => (key, Some(value)),
+                None => (field, None),
+            };
+
+            let flag = match (key, value) {
+                ("timeout", Some(value)) => {
+                    wire.timeout = Some(time::Duration::from_millis(parse_u64(value)?));
+                    FLAG_TIMEOUT
+                },
+                ("deadline", Some(value)) => {
+                    wire.deadline = Some(from_unix_millis(parse_u64(value)?)?);
+                    FLAG_DEADLINE
+                },
+                ("name", Some(value)) => {
+                    wire.name = Some(percent_decode(value)?);
+                    FLAG_NAME
+                },
+                ("cancelled", None) => {
+                    wire.cancelled = true;
+                    FLAG_CANCELLED
+                },
+                ("baggage", Some(value)) => {
+                    wire.baggage = decode_baggage(value)?;
+                    FLAG_BAGGAGE
+                },
+                ("timeout" | "deadline" | "name" | "cancelled" | "baggage", _) => {
+                    return Err(DecodeError("invalid field"));
+                },
+                _ => continue,
+            };
+
+            if seen & flag != 0 {
+                return Err(DecodeError("duplicate field"));
+            }
+            seen |= flag;
+        }
+
+        Ok(wire)
+    }
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+fn from_unix_millis(millis: u64) -> Result<SystemTime, DecodeError> {
+    UNIX_EPOCH.checked_add(time::Duration::from_millis(millis))
+        .ok_or(DecodeError("deadline out of range"))
+}
+
+fn parse_u64(value: &str) -> Result<u64, DecodeError> {
+    let value = value.trim();
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(DecodeError("invalid number"));
+    }
+
+    value.parse().map_err(|_| DecodeError("number out of range"))
+}
+
+fn encode_baggage(baggage: &BTreeMap<String, String>) -> String {
+    let mut encoded = String::new();
+    for (i, (key, value)) in baggage.iter().enumerate() {
+        if i > 0 {
+            encoded.push(',');
+        }
+        encoded.push_str(&percent_encode(key));
+        encoded.push('=');
+        encoded.push_str(&percent_encode(value));
+    }
+
+    encoded
+}
+
+fn decode_baggage(value: &str) -> Result<BTreeMap<String, String>, DecodeError> {
+    let mut baggage = BTreeMap::new();
+    for pair in value.trim().split(',').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=')
+            .ok_or(DecodeError("invalid baggage"))?;
+        if baggage.insert(percent_decode(key)?, percent_decode(value)?).is_some() {
+            return Err(DecodeError("duplicate baggage key"));
+        }
+    }
+
+    Ok(baggage)
+}
+
+/// percent-encode every byte but the unreserved characters of RFC 3986.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(b as char);
+        } else {
+            let _ = write!(encoded, "%{:02X}", b);
+        }
+    }
+
+    encoded
+}
+
+fn percent_decode(value: &str) -> Result<String, DecodeError> {
+    let mut decoded = Vec::with_capacity(value.len());
+    let mut bytes = value.bytes();
+
+    while let Some(b) = bytes.next() {
+        if b == b'%' {
+            let hex = [
+                bytes.next().ok_or(DecodeError("invalid percent-encoding"))?,
+                bytes.next().ok_or(DecodeError("invalid percent-encoding"))?,
+            ];
+            // `from_str_radix` accepts a sign, such as `%+1`.
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return Err(DecodeError("invalid percent-encoding"));
+            }
+            let hex = std::str::from_utf8(&hex).map_err(|_| DecodeError("invalid percent-encoding"))?;
+            decoded.push(u8::from_str_radix(hex, 16).map_err(|_| DecodeError("invalid percent-encoding"))?);
+        } else {
+            decoded.push(b);
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|_| DecodeError("invalid utf-8"))
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn write_bytes(bytes: &mut Vec<u8>, value: &[u8]) {
+    write_varint(bytes, value.len() as u64);
+    bytes.extend_from_slice(value);
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        let (&b, rest) = self.0.split_first()
+            .ok_or(DecodeError("unexpected end"))?;
+        self.0 = rest;
+        Ok(b)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            let bits = u64::from(b & 0x7f);
+            if shift == 63 && bits > 1 {
+                return Err(DecodeError("varint overflow"));
+            }
+            value |= bits << shift;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(DecodeError("varint overflow"))
+    }
+
+    fn string(&mut self) -> Result<String, DecodeError> {
+        let len = self.varint()?;
+        let len = usize::try_from(len).ok()
+            .filter(|&len| len <= self.0.len())
+            .ok_or(DecodeError("unexpected end"))?;
+
+        let (value, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        String::from_utf8(value.to_vec()).map_err(|_| DecodeError("invalid utf-8"))
+    }
+}
@@ -9,9 +9,9 @@ pub trait With<Output> {
 }
 
 #[async_trait::async_trait]
-impl<'a, Output, Fut> With<Output> for Fut
+impl<Output, Fut> With<Output> for Fut
 where
-    Fut: Future<Output = Output> + Send + 'a
+    Fut: Future<Output = Output> + Send
 {
     async fn with<Ctx>(self, ctx: Ctx) -> crate::Result<Output>
     where
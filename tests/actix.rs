@@ -0,0 +1,119 @@
+#![cfg(feature = "actix-web-from-request")]
+
+use std::time;
+use actix_web::http::header::HeaderName;
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App, HttpResponse};
+use context_async::{Context, Error, Timer, TimerConfig};
+
+async fn deadline(ctx: Timer) -> HttpResponse {
+    let remaining = ctx.deadline().await
+        .map(|deadline| deadline.saturating_duration_since(time::Instant::now()).as_millis().to_string())
+        .unwrap_or_else(|| String::from("none"));
+
+    HttpResponse::Ok().body(remaining)
+}
+
+async fn same(a: Timer, b: Timer) -> HttpResponse {
+    a.cancel().await;
+    HttpResponse::Ok().body(b.is_cancelled().await.to_string())
+}
+
+async fn fails(ctx: Timer) -> Result<HttpResponse, Error> {
+    ctx.cancel().await;
+    ctx.handle(async { HttpResponse::Ok().finish() }).await
+}
+
+async fn remaining_millis(body: web::Bytes) -> Option<u128> {
+    std::str::from_utf8(&body).unwrap().parse().ok()
+}
+
+#[actix_web::test]
+async fn from_request_without_config() {
+    let app = test::init_service(App::new().route("/", web::get().to(deadline))).await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, "none");
+
+    let req = test::TestRequest::get().uri("/")
+        .insert_header(("X-Request-Timeout", "1500"))
+        .to_request();
+    let remaining = remaining_millis(test::call_and_read_body(&app, req).await).await.unwrap();
+    assert!(remaining > 1000 && remaining <= 1500);
+}
+
+#[actix_web::test]
+async fn from_request_with_config() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(
+                TimerConfig::new()
+                    .header(HeaderName::from_static("grpc-timeout"))
+                    .default_timeout(time::Duration::from_secs(5))
+            ))
+            .route("/", web::get().to(deadline))
+    ).await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let remaining = remaining_millis(test::call_and_read_body(&app, req).await).await.unwrap();
+    assert!(remaining > 4500 && remaining <= 5000);
+
+    let req = test::TestRequest::get().uri("/")
+        .insert_header(("grpc-timeout", "2S"))
+        .to_request();
+    let remaining = remaining_millis(test::call_and_read_body(&app, req).await).await.unwrap();
+    assert!(remaining > 1500 && remaining <= 2000);
+
+    // a malformed header falls back to the default.
+    let req = test::TestRequest::get().uri("/")
+        .insert_header(("grpc-timeout", "2X"))
+        .to_request();
+    let remaining = remaining_millis(test::call_and_read_body(&app, req).await).await.unwrap();
+    assert!(remaining > 4500 && remaining <= 5000);
+}
+
+#[actix_web::test]
+async fn from_request_shared_in_request() {
+    let app = test::init_service(App::new().route("/", web::get().to(same))).await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, "true");
+}
+
+#[actix_web::test]
+async fn from_request_root_and_scope() {
+    let root = Timer::background();
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    let sender = std::sync::Arc::new(std::sync::Mutex::new(Some(sender)));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(TimerConfig::new().root(root.clone()))
+            .route("/", web::get().to(move |ctx: Timer| {
+                let _ = sender.lock().unwrap().take().unwrap().send(ctx.clone());
+                async move { HttpResponse::Ok().finish() }
+            }))
+    ).await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let ctx = receiver.await.unwrap();
+    drop(resp);
+
+    // the request is over, its context is cancelled, but not the root.
+    assert!(ctx.is_cancelled().await);
+    assert!(!root.is_cancelled().await);
+}
+
+#[actix_web::test]
+async fn response_error() {
+    let app = test::init_service(App::new().route("/", web::get().to(fails))).await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
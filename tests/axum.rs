@@ -0,0 +1,117 @@
+#![cfg(feature = "axum")]
+
+use std::time;
+use axum::body::Body;
+use axum::routing::get;
+use axum::Router;
+use tower::ServiceExt;
+use context_async::{Context, Timer};
+
+async fn remaining(ctx: Timer) -> String {
+    match ctx.deadline().await {
+        Some(deadline) => deadline.saturating_duration_since(time::Instant::now()).as_millis().to_string(),
+        None => String::from("none"),
+    }
+}
+
+async fn same(a: Timer, b: Timer) -> String {
+    a.cancel().await;
+    b.is_cancelled().await.to_string()
+}
+
+async fn call(app: Router, req: http::Request<Body>) -> String {
+    let resp = app.oneshot(req).await.unwrap();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn extract_without_header() {
+    let app = Router::new().route("/", get(remaining));
+    let req = http::Request::get("/").body(Body::empty()).unwrap();
+
+    assert_eq!(call(app, req).await, "none");
+}
+
+#[tokio::test]
+async fn extract_from_header() {
+    let app = Router::new().route("/", get(remaining));
+
+    let req = http::Request::get("/")
+        .header("x-request-timeout", "1500")
+        .body(Body::empty())
+        .unwrap();
+    let remaining: u128 = call(app.clone(), req).await.parse().unwrap();
+    assert!(remaining > 1000 && remaining <= 1500);
+
+    let req = http::Request::get("/")
+        .header("x-request-timeout", "3S")
+        .body(Body::empty())
+        .unwrap();
+    let remaining: u128 = call(app, req).await.parse().unwrap();
+    assert!(remaining > 2500 && remaining <= 3000);
+}
+
+#[tokio::test]
+async fn extract_shared_in_request() {
+    let app = Router::new().route("/", get(same));
+    let req = http::Request::get("/").body(Body::empty()).unwrap();
+
+    assert_eq!(call(app, req).await, "true");
+}
+
+#[tokio::test]
+async fn extract_outlives_handler() {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let app = Router::new().route("/", get(move |ctx: Timer| async move {
+        sender.send(ctx).unwrap();
+    }));
+
+    let req = http::Request::get("/").body(Body::empty()).unwrap();
+    call(app, req).await;
+
+    // a streaming body may still use the context once the handler returns.
+    let ctx = receiver.recv().await.unwrap();
+    assert!(!ctx.is_cancelled().await);
+}
+
+#[cfg(feature = "tower")]
+fn with_layer(router: Router, timeout: time::Duration) -> Router {
+    use axum::error_handling::HandleErrorLayer;
+    use context_async::{BoxError, ContextLayer, Error};
+
+    router.layer(
+        tower::ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(|err: BoxError| async move {
+                match err.downcast_ref::<Error>() {
+                    Some(Error::ContextTimeout) => http::StatusCode::GATEWAY_TIMEOUT,
+                    _ => http::StatusCode::INTERNAL_SERVER_ERROR,
+                }
+            }))
+            .layer(ContextLayer::new().timeout(timeout))
+    )
+}
+
+#[cfg(feature = "tower")]
+#[tokio::test]
+async fn extract_from_layer() {
+    let app = with_layer(Router::new().route("/", get(remaining)), time::Duration::from_secs(2));
+
+    // the extractor returns the context installed by the layer.
+    let req = http::Request::get("/").body(Body::empty()).unwrap();
+    let remaining: u128 = call(app, req).await.parse().unwrap();
+    assert!(remaining > 1500 && remaining <= 2000);
+}
+
+#[cfg(feature = "tower")]
+#[tokio::test]
+async fn layer_timeout_response() {
+    let slow = Router::new().route("/", get(|| async {
+        tokio::time::sleep(time::Duration::from_secs(10)).await;
+    }));
+    let app = with_layer(slow, time::Duration::from_millis(100));
+
+    let req = http::Request::get("/").body(Body::empty()).unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), http::StatusCode::GATEWAY_TIMEOUT);
+}
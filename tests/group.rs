@@ -0,0 +1,116 @@
+use std::time;
+use context_async::{Context, Error, TaskGroup, TimeChecker, Timer};
+
+#[derive(Debug, PartialEq)]
+enum MyError {
+    Context(Error),
+    Backend(&'static str),
+}
+
+impl From<Error> for MyError {
+    fn from(value: Error) -> Self {
+        Self::Context(value)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn group_all_ok() {
+    let timer = Timer::background();
+    let mut group = TaskGroup::<u64, MyError>::new(&timer).await;
+
+    for i in 0..5 {
+        group.spawn(async move {
+            tokio::time::sleep(time::Duration::from_millis(50 * (5 - i))).await;
+            Ok(i)
+        }).await;
+    }
+
+    // outputs keep the spawn order.
+    assert_eq!(group.wait().await, Ok(vec![0, 1, 2, 3, 4]));
+    assert!(!timer.is_cancelled().await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn group_first_error_cancels_siblings() {
+    let tc = TimeChecker::new();
+    let timer = Timer::background();
+    let mut group = TaskGroup::<u64, MyError>::new(&timer).await;
+    let group_timer = group.timer().clone();
+
+    group.spawn(async {
+        tokio::time::sleep(time::Duration::from_secs(10)).await;
+        Ok(1)
+    }).await;
+    group.spawn(async {
+        tokio::time::sleep(time::Duration::from_millis(100)).await;
+        Err(MyError::Backend("first"))
+    }).await;
+    group.spawn(async {
+        tokio::time::sleep(time::Duration::from_millis(300)).await;
+        Err(MyError::Backend("second"))
+    }).await;
+
+    assert_eq!(group.wait().await, Err(MyError::Backend("first")));
+    assert!(group_timer.is_cancelled().await);
+    assert!(!timer.is_cancelled().await);
+    assert!(tc.not_exceed(time::Duration::from_millis(300)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn group_parent_cancelled() {
+    let timer = Timer::background();
+    let mut group = TaskGroup::<(), MyError>::new(&timer).await;
+
+    group.spawn(async {
+        tokio::time::sleep(time::Duration::from_secs(10)).await;
+        Ok(())
+    }).await;
+
+    let t = timer.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(time::Duration::from_millis(50)).await;
+        t.cancel().await;
+    });
+
+    assert_eq!(group.wait().await, Err(MyError::Context(Error::ContextCancelled)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn group_timeout() {
+    let timer = Timer::in_milliseconds(100);
+    let mut group = TaskGroup::<(), MyError>::new(&timer).await;
+
+    group.spawn(async { Ok(()) }).await;
+    group.spawn(async {
+        tokio::time::sleep(time::Duration::from_secs(10)).await;
+        Ok(())
+    }).await;
+
+    assert_eq!(group.wait().await, Err(MyError::Context(Error::ContextTimeout)));
+}
+
+#[tokio::test]
+async fn group_empty() {
+    let group = TaskGroup::<(), Error>::new(Timer::background()).await;
+    assert_eq!(group.wait().await, Ok(vec![]));
+}
+
+#[tokio::test(start_paused = true)]
+async fn group_dropped_cancels() {
+    let timer = Timer::background();
+    let mut group = TaskGroup::<(), MyError>::new(&timer).await;
+    let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+
+    group.spawn(async move {
+        let _sender = sender;
+        std::future::pending().await
+    }).await;
+
+    // dropped without `wait`: the task is stopped, and drops its sender.
+    let ctx = group.timer().clone();
+    drop(group);
+    let stopped = tokio::time::timeout(time::Duration::from_secs(1), receiver).await;
+    assert!(matches!(stopped, Ok(Err(_))));
+    assert!(ctx.is_cancelled().await);
+    assert!(!timer.is_cancelled().await);
+}
@@ -0,0 +1,104 @@
+use std::io;
+use std::time;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use context_async::{Context, ContextIo, Error, TimeChecker, Timer};
+
+#[tokio::test]
+async fn io_read_write() {
+    let timer = Timer::background();
+    let (client, server) = tokio::io::duplex(64);
+    let mut client = ContextIo::new(client, &timer);
+    let mut server = ContextIo::new(server, &timer);
+
+    client.write_all(b"hello").await.unwrap();
+    client.shutdown().await.unwrap();
+
+    let mut buf = Vec::new();
+    server.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, b"hello");
+}
+
+#[test]
+fn io_outside_runtime() {
+    let timer = Timer::in_seconds(5);
+    let (client, _server) = tokio::io::duplex(64);
+    let mut client = ContextIo::new(client, &timer);
+
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+    runtime.block_on(client.write_all(b"hello")).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn io_read_cancelled() {
+    let timer = Timer::background();
+    let child = timer.spawn().await;
+    let (client, _server) = tokio::io::duplex(64);
+    let mut client = ContextIo::new(client, child);
+
+    let reader = tokio::spawn(async move {
+        let mut buf = [0u8; 16];
+        client.read(&mut buf).await
+    });
+
+    tokio::time::sleep(time::Duration::from_millis(50)).await;
+    timer.cancel().await;
+
+    let err = reader.await.unwrap().err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    assert_eq!(err.into_inner().unwrap().downcast::<Error>().ok().as_deref(), Some(&Error::ContextCancelled));
+}
+
+#[tokio::test]
+async fn io_retry_after_cancel() {
+    let timer = Timer::background();
+    let (client, mut server) = tokio::io::duplex(64);
+    let mut client = ContextIo::new(client, &timer);
+    server.write_all(b"hello").await.unwrap();
+
+    timer.cancel().await;
+
+    // `Interrupted` is retried by callers, the error must be the same on every call.
+    for _ in 0..2 {
+        let mut buf = [0u8; 16];
+        let err = client.read(&mut buf).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+
+        let err = client.write(b"hello").await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+
+        let err = client.flush().await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn io_copy_timeout() {
+    let tc = TimeChecker::new();
+    let timer = Timer::in_milliseconds(300);
+
+    // the server never closes, so `copy` only ends with the context.
+    let (client, mut server) = tokio::io::duplex(64);
+    server.write_all(b"partial").await.unwrap();
+
+    let mut client = ContextIo::new(client, &timer);
+    let mut sink = Vec::new();
+    let err = tokio::io::copy(&mut client, &mut sink).await.err().unwrap();
+
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    assert_eq!(sink, b"partial");
+    assert!(tc.not_exceed(time::Duration::from_millis(600)));
+}
+
+#[tokio::test]
+async fn io_write_after_cancel() {
+    let timer = Timer::background();
+    let (client, _server) = tokio::io::duplex(64);
+    let mut client = ContextIo::new(client, &timer);
+
+    timer.cancel().await;
+    let err = client.write_all(b"hello").await.err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+
+    // shutdown is still allowed.
+    client.shutdown().await.unwrap();
+}
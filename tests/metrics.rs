@@ -0,0 +1,154 @@
+#![cfg(feature = "metrics")]
+
+use std::time;
+use std::collections::HashMap;
+use metrics::{Key, Label, SharedString, Unit};
+use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+use metrics_util::{CompositeKey, MetricKind};
+use context_async::{Context, Error, Operation, Timer};
+
+/// the metrics recorded since the last snapshot, histograms are drained by each snapshot.
+type Snapshot = HashMap<CompositeKey, (Option<Unit>, Option<SharedString>, DebugValue)>;
+
+fn take_snapshot(snapshotter: &Snapshotter) -> Snapshot {
+    snapshotter.snapshot().into_hashmap()
+}
+
+fn key(kind: MetricKind, name: &'static str, labels: &[(&'static str, &'static str)]) -> CompositeKey {
+    let labels: Vec<_> = labels.iter().map(|&(key, value)| Label::new(key, value)).collect();
+    CompositeKey::new(kind, Key::from_parts(name, labels))
+}
+
+fn counter(snapshot: &Snapshot, name: &'static str, labels: &[(&'static str, &'static str)]) -> u64 {
+    let key = key(MetricKind::Counter, name, labels);
+    snapshot.get(&key)
+        .map(|(_, _, value)| match value {
+            DebugValue::Counter(value) => *value,
+            _ => unreachable!(),
+        })
+        .unwrap_or(0)
+}
+
+fn histogram(snapshot: &Snapshot, name: &'static str, labels: &[(&'static str, &'static str)]) -> Vec<f64> {
+    let key = key(MetricKind::Histogram, name, labels);
+    snapshot.get(&key)
+        .map(|(_, _, value)| match value {
+            DebugValue::Histogram(values) => values.iter().map(|value| value.into_inner()).collect(),
+            _ => unreachable!(),
+        })
+        .unwrap_or_default()
+}
+
+#[tokio::test(start_paused = true)]
+async fn metrics_handle() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let _guard = metrics::set_default_local_recorder(&recorder);
+
+    let ctx = Timer::in_seconds(10).with_value::<Operation>(String::from("checkout")).await;
+    let op = [("operation", "checkout")];
+
+    ctx.handle(tokio::time::sleep(time::Duration::from_secs(4))).await.unwrap();
+
+    let child = ctx.spawn_in_seconds(1).await;
+    let err = child.handle(tokio::time::sleep(time::Duration::from_secs(4))).await;
+    assert_eq!(err, Err(Error::ContextTimeout));
+
+    let child = ctx.spawn().await;
+    child.cancel().await;
+    assert_eq!(child.handle(async {}).await, Err(Error::ContextCancelled));
+
+    let snapshot = take_snapshot(&snapshotter);
+    assert_eq!(counter(&snapshot, "context_async_handle_total", &[("operation", "checkout"), ("outcome", "ok")]), 1);
+    assert_eq!(counter(&snapshot, "context_async_handle_total", &[("operation", "checkout"), ("outcome", "timeout")]), 1);
+    assert_eq!(counter(&snapshot, "context_async_handle_total", &[("operation", "checkout"), ("outcome", "cancelled")]), 1);
+
+    assert_eq!(histogram(&snapshot, "context_async_handle_duration_seconds", &[("operation", "checkout"), ("outcome", "ok")]), [4.0]);
+    assert_eq!(histogram(&snapshot, "context_async_handle_duration_seconds", &[("operation", "checkout"), ("outcome", "timeout")]), [1.0]);
+    assert_eq!(histogram(&snapshot, "context_async_remaining_seconds", &op), [6.0]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn metrics_contexts() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let _guard = metrics::set_default_local_recorder(&recorder);
+
+    let root = Timer::background();
+    let ctx = root.with_value::<Operation>(String::from("sync")).await;
+    let child = ctx.spawn().await;
+    let timeout = ctx.spawn_in_seconds(1).await;
+    let op = [("operation", "sync")];
+
+    let snapshot = take_snapshot(&snapshotter);
+    assert_eq!(counter(&snapshot, "context_async_created_total", &[("operation", "unlabeled")]), 1);
+    assert_eq!(counter(&snapshot, "context_async_created_total", &op), 3);
+    tokio::time::sleep(time::Duration::from_secs(2)).await;
+
+    // a passed deadline is not counted until the timeout is observed.
+    let work = ctx.spawn_in_seconds(1).await;
+    work.handle(async {}).await.unwrap();
+    tokio::time::sleep(time::Duration::from_secs(2)).await;
+    drop(work);
+    let snapshot = take_snapshot(&snapshotter);
+    assert_eq!(counter(&snapshot, "context_async_timeout_total", &op), 0);
+
+    // observed by `handle` and `done`, and counted once for the context, not for its childs.
+    let inherited = timeout.spawn().await;
+    assert_eq!(timeout.handle(async {}).await, Err(Error::ContextTimeout));
+    assert_eq!(timeout.done().await, Error::ContextTimeout);
+    let snapshot = take_snapshot(&snapshotter);
+    assert_eq!(counter(&snapshot, "context_async_timeout_total", &op), 1);
+    drop(inherited);
+
+    let fired = ctx.spawn_in_seconds(1).await.fire_on_deadline();
+    let fired_child = fired.spawn().await;
+    tokio::time::sleep(time::Duration::from_secs(2)).await;
+    let snapshot = take_snapshot(&snapshotter);
+    assert_eq!(counter(&snapshot, "context_async_timeout_total", &op), 2);
+    assert!(fired_child.is_cancelled().await);
+    drop((fired, fired_child, timeout));
+
+    ctx.cancel().await;
+    drop(child);
+    let snapshot = take_snapshot(&snapshotter);
+    assert_eq!(counter(&snapshot, "context_async_cancelled_total", &op), 2);
+    assert_eq!(counter(&snapshot, "context_async_cancelled_total", &[("operation", "unlabeled")]), 0);
+}
+
+#[cfg(feature = "name")]
+#[tokio::test]
+async fn metrics_name_not_label() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let _guard = metrics::set_default_local_recorder(&recorder);
+
+    let root = Timer::with_name("tenant-42".parse().unwrap());
+    let db = root.spawn_named("db").await;
+    let query = db.with_value::<Operation>(String::from("query")).await;
+    query.handle(async {}).await.unwrap();
+
+    let snapshot = take_snapshot(&snapshotter);
+    assert_eq!(counter(&snapshot, "context_async_created_total", &[("operation", "unlabeled")]), 2);
+    assert_eq!(counter(&snapshot, "context_async_created_total", &[("operation", "tenant")]), 0);
+    assert_eq!(counter(&snapshot, "context_async_created_total", &[("operation", "query")]), 1);
+    assert_eq!(counter(&snapshot, "context_async_handle_total", &[("operation", "query"), ("outcome", "ok")]), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn metrics_timeout_queried() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let _guard = metrics::set_default_local_recorder(&recorder);
+
+    let ctx = Timer::in_seconds(1).with_value::<Operation>(String::from("poll")).await;
+    let op = [("operation", "poll")];
+    assert!(!ctx.is_timeout().await);
+    tokio::time::sleep(time::Duration::from_secs(2)).await;
+
+    // a timeout seen by `is_timeout` or `error` is counted, once.
+    assert!(ctx.is_timeout().await);
+    assert_eq!(ctx.error().await, Some(Error::ContextTimeout));
+    let snapshot = take_snapshot(&snapshotter);
+    assert_eq!(counter(&snapshot, "context_async_timeout_total", &op), 1);
+}
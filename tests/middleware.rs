@@ -0,0 +1,125 @@
+#![cfg(feature = "actix-web-middleware")]
+
+use std::time;
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App, HttpResponse};
+use context_async::{Context, Error, TimeChecker, Timer, TimerConfig, TimerMiddleware};
+
+async fn slow(ctx: Timer) -> Result<HttpResponse, Error> {
+    // the handler ignores its context, the middleware stops it anyway.
+    tokio::time::sleep(time::Duration::from_secs(10)).await;
+    Ok(HttpResponse::Ok().body(ctx.is_done().to_string()))
+}
+
+async fn remaining(ctx: Timer) -> HttpResponse {
+    let remaining = ctx.deadline().await.unwrap()
+        .saturating_duration_since(time::Instant::now());
+    HttpResponse::Ok().body(remaining.as_millis().to_string())
+}
+
+async fn fails(ctx: Timer) -> Result<HttpResponse, Error> {
+    ctx.cancel().await;
+    ctx.handle(async { HttpResponse::Ok().finish() }).await
+}
+
+#[actix_web::test]
+async fn middleware_timeout() {
+    let tc = TimeChecker::new();
+    let app = test::init_service(
+        App::new()
+            .wrap(TimerMiddleware::new(time::Duration::from_millis(200)))
+            .route("/", web::get().to(slow))
+    ).await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let err = test::try_call_service(&app, req).await.err().unwrap();
+    let resp = err.error_response();
+
+    assert_eq!(resp.status(), StatusCode::GATEWAY_TIMEOUT);
+    assert_eq!(err.to_string(), "context timeout");
+    assert!(tc.not_exceed(time::Duration::from_millis(500)));
+}
+
+#[actix_web::test]
+async fn middleware_shares_timer() {
+    let app = test::init_service(
+        App::new()
+            .service(
+                web::resource("/")
+                    .wrap(TimerMiddleware::new(time::Duration::from_secs(2)))
+                    .route(web::get().to(remaining))
+            )
+    ).await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    let remaining: u128 = std::str::from_utf8(&body).unwrap().parse().unwrap();
+    assert!(remaining > 1500 && remaining <= 2000);
+}
+
+#[actix_web::test]
+async fn middleware_nested_shortens() {
+    let app = test::init_service(
+        App::new()
+            .app_data(TimerConfig::new().default_timeout(time::Duration::from_secs(10)))
+            .wrap(TimerMiddleware::unlimited())
+            .service(
+                web::resource("/")
+                    .wrap(TimerMiddleware::new(time::Duration::from_secs(1)))
+                    .route(web::get().to(remaining))
+            )
+    ).await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    let remaining: u128 = std::str::from_utf8(&body).unwrap().parse().unwrap();
+    assert!(remaining > 500 && remaining <= 1000);
+}
+
+#[actix_web::test]
+async fn middleware_root_cancelled() {
+    let root = Timer::background();
+    let app = test::init_service(
+        App::new()
+            .app_data(TimerConfig::new().root(root.clone()))
+            .wrap(
+                TimerMiddleware::new(time::Duration::from_secs(10))
+                    .cancelled_status(StatusCode::from_u16(499).unwrap())
+            )
+            .route("/", web::get().to(slow))
+    ).await;
+
+    let r = root.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(time::Duration::from_millis(100)).await;
+        r.cancel().await;
+    });
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let err = test::try_call_service(&app, req).await.err().unwrap();
+    assert_eq!(err.error_response().status().as_u16(), 499);
+}
+
+#[actix_web::test]
+async fn middleware_config_status() {
+    let app = test::init_service(
+        App::new()
+            .app_data(
+                TimerConfig::new()
+                    .timeout_status(StatusCode::REQUEST_TIMEOUT)
+                    .cancelled_status(StatusCode::from_u16(499).unwrap())
+            )
+            .service(web::resource("/fails").wrap(TimerMiddleware::unlimited()).route(web::get().to(fails)))
+            .service(web::resource("/slow").wrap(TimerMiddleware::new(time::Duration::from_millis(100))).route(web::get().to(slow)))
+    ).await;
+
+    // the error returned by the handler.
+    let req = test::TestRequest::get().uri("/fails").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status().as_u16(), 499);
+
+    // the timeout of the middleware.
+    let req = test::TestRequest::get().uri("/slow").to_request();
+    let err = test::try_call_service(&app, req).await.err().unwrap();
+    assert_eq!(err.error_response().status(), StatusCode::REQUEST_TIMEOUT);
+}
@@ -0,0 +1,188 @@
+#![cfg(feature = "name")]
+
+use std::time;
+use context_async::{Context, Key, Name, NameGenerator, Timer};
+
+struct RequestId;
+
+impl Key for RequestId {
+    type Value = u64;
+}
+
+#[tokio::test]
+async fn name_path() {
+    let root = Timer::named("req");
+    let db = root.spawn_named("db").await;
+    let query = db.spawn().await;
+
+    let root_name = root.name().await;
+    let db_name = db.name().await;
+    let name = query.name().await;
+
+    assert_eq!(root_name.to_string(), format!("req-{}", root_name.id()));
+    assert_eq!(db_name.to_string(), format!("req-{}/db-{}", root_name.id(), db_name.id()));
+    assert_eq!(name.to_string(), format!("{}/{}", db_name, name.id()));
+
+    assert_eq!(name.label(), None);
+    assert_eq!(name.parent(), Some(&db_name));
+    assert_eq!(name.root(), &root_name);
+    assert_eq!(name.depth(), 2);
+    assert_eq!(root_name.depth(), 0);
+
+    let labels: Vec<_> = name.ancestors().map(|name| name.label()).collect();
+    assert_eq!(labels, [None, Some("db"), Some("req")]);
+}
+
+#[tokio::test]
+async fn name_unlabeled_root() {
+    let timer = Timer::background();
+    let name = timer.name().await;
+
+    assert_eq!(name.to_string(), name.id().to_string());
+    assert!(name.parent().is_none());
+    assert_eq!(timer.name().await, name);
+    assert_ne!(Timer::background().name().await, name);
+}
+
+#[tokio::test]
+async fn name_label_escaped() {
+    let timer = Timer::named("a/b");
+    assert_eq!(timer.name().await.label(), Some("a_b"));
+}
+
+#[tokio::test]
+async fn name_inherited_by_all_childs() {
+    let root = Timer::named_with_timeout("job", time::Duration::from_secs(5));
+    let root_name = root.name().await;
+
+    let value = root.with_value::<RequestId>(1).await;
+    assert_eq!(value.name().await.parent(), Some(&root_name));
+
+    let child = root.spawn_named_with_timeout("step", time::Duration::from_secs(1)).await;
+    assert_eq!(child.name().await.parent(), Some(&root_name));
+    assert_eq!(child.name().await.label(), Some("step"));
+    assert!(child.deadline().await < root.deadline().await);
+}
+
+#[tokio::test]
+async fn name_generator_per_root() {
+    let root = Timer::with_name_generator(NameGenerator::counter());
+    let child = root.spawn_named("db").await;
+    let grandchild = child.spawn().await;
+    let value = grandchild.with_value::<RequestId>(1).await;
+
+    assert_eq!(root.name().await.to_string(), "1");
+    assert_eq!(child.name().await.to_string(), "1/db-2");
+    assert_eq!(grandchild.name().await.to_string(), "1/db-2/3");
+    assert_eq!(value.name().await.to_string(), "1/db-2/3/4");
+
+    let other = Timer::with_name_generator(NameGenerator::counter());
+    assert_eq!(other.name().await.to_string(), "1");
+}
+
+#[tokio::test]
+async fn name_generator_from_fn() {
+    let generator = NameGenerator::from_fn(|| String::from("a/b-c"));
+    let root = Timer::with_name_generator(generator);
+    let child = root.spawn_named("x").await;
+
+    assert_eq!(root.name().await.id(), "a_b-c");
+    assert_eq!(root.name().await.to_string(), "-a_b-c");
+    assert_eq!(child.name().await.to_string(), "-a_b-c/x-a_b-c");
+    assert_eq!(child.name().await.to_string().parse(), Ok(child.name().await));
+}
+
+#[tokio::test]
+async fn name_generator_empty_id() {
+    let root = Timer::with_name_generator(NameGenerator::from_fn(String::new));
+    let child = root.spawn_named("x").await;
+
+    assert_eq!(root.name().await.id(), "_");
+    assert_eq!(child.name().await.to_string(), "_/x-_");
+    assert_eq!(child.name().await.to_string().parse(), Ok(child.name().await));
+}
+
+#[test]
+#[allow(deprecated)]
+fn name_as_u64() {
+    assert_eq!(Name::from(42).as_u64(), 42);
+    assert_eq!("req-42".parse::<Name>().unwrap().as_u64(), 42);
+    assert_eq!(Name::from_id("abc").as_u64(), Name::from_id("abc").as_u64());
+    assert_ne!(Name::from_id("abc").as_u64(), Name::from_id("abd").as_u64());
+}
+
+#[cfg(feature = "uuid")]
+#[tokio::test]
+async fn name_generator_uuid() {
+    let root = Timer::with_name_generator(NameGenerator::uuid_v4());
+    let id = root.spawn().await.name().await.id().to_string();
+
+    assert_eq!(id.len(), 32);
+    assert!(id.bytes().all(|b| b.is_ascii_hexdigit()));
+}
+
+#[cfg(feature = "ulid")]
+#[tokio::test]
+async fn name_generator_ulid() {
+    let root = Timer::with_name_generator(NameGenerator::ulid());
+    let first = root.spawn().await.name().await.id().to_string();
+    tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+    let second = root.spawn().await.name().await.id().to_string();
+
+    assert_eq!(first.len(), 26);
+    assert!(first < second);
+}
+
+#[test]
+fn name_parse() {
+    let name: Name = "req-42/db/query-3".parse().unwrap();
+
+    assert_eq!(name.label(), Some("query"));
+    assert_eq!(name.id(), "3");
+    assert_eq!(name.parent().unwrap().label(), None);
+    assert_eq!(name.parent().unwrap().id(), "db");
+    assert_eq!(name.root().label(), Some("req"));
+    assert_eq!(name.root().id(), "42");
+    assert_eq!(name.to_string(), "req-42/db/query-3");
+
+    let upstream: Name = "550e8400-e29b-41d4-a716-446655440000".parse().unwrap();
+    assert_eq!(upstream.to_string(), "550e8400-e29b-41d4-a716-446655440000");
+
+    for malformed in ["", "/", "a//b", "a/", "req-", "-"] {
+        assert!(malformed.parse::<Name>().is_err(), "{:?} should be malformed", malformed);
+    }
+}
+
+#[tokio::test]
+async fn name_from_upstream() {
+    let upstream: Name = "req-42".parse().unwrap();
+    let root = Timer::with_name(upstream.clone());
+    let child = root.spawn_named("db").await;
+
+    assert_eq!(root.name().await, upstream);
+    assert!(child.name().await.to_string().starts_with("req-42/db-"));
+}
+
+#[tokio::test]
+async fn name_from_upstream_id() {
+    let request_id = "550e8400-e29b-41d4-a716-446655440000";
+    let root = Timer::with_name(Name::from_id(request_id));
+    let child = root.spawn_named("db").await;
+
+    let name = root.name().await;
+    assert_eq!(name.label(), None);
+    assert_eq!(name.id(), request_id);
+    assert_eq!(child.name().await.parent(), Some(&name));
+    assert_eq!(child.name().await.to_string().parse(), Ok(child.name().await));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn name_serde() {
+    let name: Name = "req-42/db-7".parse().unwrap();
+    let json = serde_json::to_string(&name).unwrap();
+
+    assert_eq!(json, r#""req-42/db-7""#);
+    assert_eq!(serde_json::from_str::<Name>(&json).unwrap(), name);
+    assert!(serde_json::from_str::<Name>(r#""a//b""#).is_err());
+}
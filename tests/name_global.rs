@@ -0,0 +1,17 @@
+#![cfg(feature = "name")]
+
+// the process-wide generator is set in its own test binary,
+// so that it does not leak into the tests running in parallel.
+
+use context_async::{Context, NameGenerator, Timer};
+
+#[tokio::test]
+async fn name_generator_global() {
+    let counter = NameGenerator::counter();
+    NameGenerator::from_fn(move || format!("global{}", counter.generate().id())).set_global();
+    let root = Timer::background();
+    let child = root.spawn().await;
+
+    assert!(root.name().await.id().starts_with("global"));
+    assert!(child.name().await.id().starts_with("global"));
+}
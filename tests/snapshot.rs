@@ -0,0 +1,106 @@
+use std::time;
+use context_async::{Context, Snapshot, Timer};
+
+#[tokio::test(start_paused = true)]
+async fn snapshot_tree() {
+    let root = Timer::background();
+    tokio::time::sleep(time::Duration::from_secs(2)).await;
+
+    let child = root.spawn_in_seconds(5).await;
+    let grandchild = child.spawn().await;
+    let cancelled = root.spawn().await;
+    cancelled.cancel().await;
+    let dropped = root.spawn().await;
+    drop(dropped);
+
+    tokio::time::sleep(time::Duration::from_secs(1)).await;
+    let snapshot = root.snapshot();
+
+    assert_eq!(snapshot.count(), 4);
+    assert_eq!(snapshot.age, time::Duration::from_secs(3));
+    assert_eq!(snapshot.remaining, None);
+    assert!(!snapshot.cancelled);
+    assert_eq!(snapshot.childs.len(), 2);
+
+    let child_snapshot = &snapshot.childs[0];
+    assert_eq!(child_snapshot.age, time::Duration::from_secs(1));
+    assert_eq!(child_snapshot.remaining, Some(time::Duration::from_secs(4)));
+    assert_eq!(child_snapshot.childs.len(), 1);
+    assert_eq!(child_snapshot.childs[0].remaining, Some(time::Duration::from_secs(4)));
+    assert!(snapshot.childs[1].cancelled);
+
+    tokio::time::sleep(time::Duration::from_secs(4)).await;
+    let snapshot = child.snapshot();
+    assert!(snapshot.timeout);
+    assert_eq!(snapshot.remaining, Some(time::Duration::ZERO));
+
+    drop(grandchild);
+    assert!(child.snapshot().childs.is_empty());
+}
+
+fn tree() -> Snapshot {
+    Snapshot {
+        name: Some(String::from("req \"1\"")),
+        age: time::Duration::from_millis(1500),
+        remaining: Some(time::Duration::from_millis(500)),
+        cancelled: false,
+        timeout: false,
+        childs: vec![
+            Snapshot {
+                name: None,
+                age: time::Duration::from_millis(20),
+                remaining: None,
+                cancelled: true,
+                timeout: false,
+                childs: vec![],
+            },
+        ],
+    }
+}
+
+#[test]
+fn snapshot_text() {
+    assert_eq!(tree().to_string(), concat!(
+        "req \"1\" age=1.5s remaining=500ms state=active childs=1\n",
+        "  context age=20ms remaining=none state=cancelled childs=0\n",
+    ));
+}
+
+#[test]
+fn snapshot_json() {
+    assert_eq!(tree().to_json(), concat!(
+        r#"{"name":"req \"1\"","age_ms":1500,"remaining_ms":500,"cancelled":false,"timeout":false,"childs":["#,
+        r#"{"name":null,"age_ms":20,"remaining_ms":null,"cancelled":true,"timeout":false,"childs":[]}"#,
+        r#"]}"#,
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn snapshot_serde() {
+    let json = serde_json::to_value(tree()).unwrap();
+    assert_eq!(json, serde_json::from_str::<serde_json::Value>(&tree().to_json()).unwrap());
+}
+
+#[test]
+fn snapshot_dot() {
+    assert_eq!(tree().to_dot(), concat!(
+        "digraph contexts {\n",
+        "    node [shape=box];\n",
+        "    n0 [label=\"req \\\"1\\\"\\nage 1.5s\\nremaining 500ms\\nactive\"];\n",
+        "    n1 [label=\"context\\nage 20ms\\ncancelled\", style=dashed];\n",
+        "    n0 -> n1;\n",
+        "}\n",
+    ));
+}
+
+#[cfg(feature = "name")]
+#[tokio::test]
+async fn snapshot_name() {
+    let root = Timer::named("req");
+    let child = root.spawn_named("db").await;
+
+    let snapshot = root.snapshot();
+    assert_eq!(snapshot.name, Some(root.name().await.to_string()));
+    assert_eq!(snapshot.childs[0].name, Some(child.name().await.to_string()));
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "stream")]
+
+use std::time;
+use futures::{Stream, StreamExt};
+use context_async::{Context, Error, TimeChecker, Timer, WithStream};
+
+#[tokio::test]
+async fn stream_ends() {
+    let timer = Timer::background();
+    let items: Vec<_> = futures::stream::iter(0..3)
+        .with_context(&timer)
+        .collect()
+        .await;
+
+    assert_eq!(items, vec![Ok(0), Ok(1), Ok(2)]);
+}
+
+#[test]
+fn stream_outside_runtime() {
+    let timer = Timer::in_seconds(5);
+    let stream = futures::stream::iter(0..3).with_context(&timer);
+
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+    let items: Vec<_> = runtime.block_on(stream.collect());
+    assert_eq!(items, vec![Ok(0), Ok(1), Ok(2)]);
+}
+
+#[tokio::test]
+async fn stream_size_hint() {
+    let timer = Timer::background();
+
+    // one more item, for the error of the context.
+    assert_eq!(futures::stream::iter(0..3).with_context(&timer).size_hint(), (0, Some(4)));
+    assert_eq!(futures::stream::iter(0..usize::MAX).with_context(&timer).size_hint(), (0, None));
+}
+
+#[tokio::test]
+async fn stream_cancelled() {
+    let timer = Timer::background();
+    let child = timer.spawn().await;
+    let mut stream = futures::stream::iter(0..).with_context(child);
+
+    assert_eq!(stream.next().await, Some(Ok(0)));
+    assert_eq!(stream.next().await, Some(Ok(1)));
+
+    timer.cancel().await;
+    assert_eq!(stream.next().await, Some(Err(Error::ContextCancelled)));
+    assert_eq!(stream.next().await, None);
+    assert_eq!(stream.next().await, None);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stream_timeout() {
+    let tc = TimeChecker::new();
+    let timer = Timer::in_milliseconds(500);
+
+    let ticks = futures::stream::unfold((), |_| async {
+        tokio::time::sleep(time::Duration::from_millis(100)).await;
+        Some(((), ()))
+    });
+
+    let items: Vec<_> = ticks.with_context(&timer).collect().await;
+
+    assert!(items.len() >= 4);
+    assert_eq!(items.last(), Some(&Err(Error::ContextTimeout)));
+    assert!(items[..items.len() - 1].iter().all(Result::is_ok));
+    assert!(tc.not_exceed(time::Duration::from_millis(800)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn stream_pending_cancelled() {
+    let timer = Timer::background();
+    let t = timer.clone();
+
+    let task = tokio::spawn(async move {
+        futures::stream::pending::<u8>()
+            .with_context(t)
+            .collect::<Vec<_>>()
+            .await
+    });
+
+    tokio::time::sleep(time::Duration::from_millis(50)).await;
+    timer.cancel().await;
+
+    assert_eq!(task.await.unwrap(), vec![Err(Error::ContextCancelled)]);
+}
+
+#[tokio::test]
+async fn stream_until_done() {
+    let timer = Timer::background();
+    let mut stream = futures::stream::iter(0..).until_done(&timer);
+
+    assert_eq!(stream.next().await, Some(0));
+    timer.cancel().await;
+    assert_eq!(stream.next().await, None);
+}
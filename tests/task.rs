@@ -0,0 +1,59 @@
+use std::time;
+use context_async::{Context, Error, TimeChecker, Timer};
+
+#[tokio::test]
+async fn spawn_task_ok() {
+    let timer = Timer::background();
+    let handle = timer.spawn_task(async { 42 }).await;
+
+    assert_eq!(handle.await, Ok(42));
+    assert!(!timer.is_cancelled().await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn spawn_task_cancelled() {
+    let timer = Timer::background();
+
+    let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+    let handle = timer.spawn_task(async move {
+        let _sender = sender;
+        tokio::time::sleep(time::Duration::from_secs(100)).await;
+    }).await;
+
+    tokio::time::sleep(time::Duration::from_millis(50)).await;
+    timer.cancel().await;
+
+    // the future is dropped, even if nobody awaits the handle.
+    let _ = receiver.await;
+    assert!(handle.timer().is_cancelled().await);
+    assert_eq!(handle.await, Err(Error::ContextCancelled));
+}
+
+#[tokio::test(start_paused = true)]
+async fn spawn_task_timeout() {
+    let tc = TimeChecker::new();
+    let timer = Timer::in_milliseconds(300);
+    let handle = timer.spawn_task(tokio::time::sleep(time::Duration::from_secs(10))).await;
+
+    assert_eq!(handle.await, Err(Error::ContextTimeout));
+    assert!(tc.not_exceed(time::Duration::from_millis(600)));
+}
+
+#[tokio::test]
+async fn spawn_task_cancel_handle() {
+    let timer = Timer::background();
+    let handle = timer.spawn_task(std::future::pending::<()>()).await;
+
+    handle.cancel().await;
+    assert!(!timer.is_cancelled().await);
+    assert_eq!(handle.await, Err(Error::ContextCancelled));
+}
+
+#[tokio::test]
+#[should_panic(expected = "boom")]
+async fn spawn_task_panic() {
+    let timer = Timer::background();
+    let handle = timer.spawn_task(async { panic!("boom") }).await;
+
+    let _: () = handle.await.unwrap();
+}
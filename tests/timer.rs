@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time;
 use context_async::{Context, Error, TimeChecker, Timer};
 
@@ -71,43 +72,61 @@ async fn test_timer_cancel() {
     assert_eq!(child_2_1_1.error().await, Some(Error::ContextCancelled));
 }
 
-#[tokio::test]
+#[tokio::test(start_paused = true)]
 async fn test_timer_timeout() {
     let timer = Timer::with_timeout(time::Duration::from_secs(1));
     assert!(!timer.is_timeout().await);
 
-    tokio::time::sleep(time::Duration::from_secs(2)).await;
+    tokio::time::advance(time::Duration::from_secs(2)).await;
     assert!(timer.is_timeout().await);
 
     // check child timeout...
     let timer = Timer::with_timeout(time::Duration::from_secs(10));
     let child = timer.spawn_with_timeout(time::Duration::from_secs(1)).await;
 
-    tokio::time::sleep(time::Duration::from_secs(2)).await;
+    tokio::time::advance(time::Duration::from_secs(2)).await;
     assert!(!timer.is_timeout().await);
     assert!(child.is_timeout().await);
 
     let timer = Timer::with_timeout(time::Duration::from_secs(5));
     let child = timer.spawn_with_timeout(time::Duration::from_secs(10)).await;
 
-    tokio::time::sleep(time::Duration::from_secs(2)).await;
+    tokio::time::advance(time::Duration::from_secs(2)).await;
     assert!(!timer.is_timeout().await);
     assert!(!child.is_timeout().await);
 
-    tokio::time::sleep(time::Duration::from_secs(4)).await;
+    tokio::time::advance(time::Duration::from_secs(4)).await;
     assert!(timer.is_timeout().await);
     assert!(child.is_timeout().await);
     assert_eq!(timer.error().await, Some(Error::ContextTimeout));
     assert_eq!(child.error().await, Some(Error::ContextTimeout));
 }
 
-#[tokio::test]
+#[tokio::test(start_paused = true)]
+async fn timer_paused_clock() {
+    let start = tokio::time::Instant::now();
+    let timer = Timer::in_seconds(60);
+    let child = timer.spawn().await;
+
+    assert_eq!(timer.deadline().await, Some((start + time::Duration::from_secs(60)).into_std()));
+
+    tokio::time::advance(time::Duration::from_secs(59)).await;
+    assert!(!child.try_is_timeout());
+    assert!(child.try_error().is_none());
+
+    tokio::time::advance(time::Duration::from_secs(1)).await;
+    assert!(child.try_is_timeout());
+    assert_eq!(child.try_error(), Some(Error::ContextTimeout));
+    assert_eq!(timer.done().await, Error::ContextTimeout);
+}
+
+#[tokio::test(start_paused = true)]
 async fn timer_handle_simple() {
     let timer = Timer::todo();
     timer.handle(tokio::time::sleep(time::Duration::from_secs(1))).await.unwrap();
 }
 
-#[tokio::test]
+#[tokio::test(start_paused = true)]
 async fn timer_handle_timeout() {
     let tc = TimeChecker::new();
     let timer = Timer::with_timeout(time::Duration::from_secs(1));
@@ -118,7 +137,7 @@ async fn timer_handle_timeout() {
     assert!(tc.not_exceed(time::Duration::from_millis(1300)));
 }
 
-#[tokio::test]
+#[tokio::test(start_paused = true)]
 async fn timer_handle_timeout_2() {
     let tc = TimeChecker::new();
 
@@ -141,7 +160,7 @@ async fn timer_handle_timeout_2() {
     assert!(tc.not_exceed(time::Duration::from_millis(4300)));
 }
 
-#[tokio::test]
+#[tokio::test(start_paused = true)]
 async fn timer_partial_cancel() {
     let tc = TimeChecker::new();
 
@@ -190,3 +209,449 @@ async fn timer_handle_result() {
 
     timer.handle_result(my_func()).await.unwrap();
 }
+
+#[tokio::test(start_paused = true)]
+async fn timer_cancel_with_reason() {
+    #[derive(Debug)]
+    struct Shutdown;
+
+    impl std::fmt::Display for Shutdown {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("shutdown")
+        }
+    }
+
+    impl std::error::Error for Shutdown {}
+
+    let timer = Timer::background();
+    let child = timer.spawn().await;
+    let child_of_child = child.spawn().await;
+
+    let t = child_of_child.clone();
+    let task = tokio::spawn(async move {
+        t.handle(tokio::time::sleep(time::Duration::from_secs(10))).await
+    });
+
+    let cause: Arc<dyn std::error::Error + Send + Sync> = Arc::new(Shutdown);
+    child.cancel_with_reason(cause.clone()).await;
+
+    assert!(!timer.is_cancelled().await);
+    assert!(child.is_cancelled().await);
+    assert!(child_of_child.is_cancelled().await);
+    assert_eq!(child.error().await, Some(Error::ContextCancelledWith(cause.clone())));
+    assert_eq!(child_of_child.error().await, Some(Error::ContextCancelledWith(cause.clone())));
+
+    let err = task.await.unwrap().err().unwrap();
+    assert!(err.is_cancelled());
+    assert!(err.cause().unwrap().is::<Shutdown>());
+    assert_eq!(err.to_string(), "context cancelled: shutdown");
+
+    // the first cancellation wins.
+    child.cancel().await;
+    assert_eq!(child.error().await, Some(Error::ContextCancelledWith(cause)));
+
+    // a plain cancel has no cause.
+    timer.cancel().await;
+    assert_eq!(timer.error().await, Some(Error::ContextCancelled));
+}
+
+fn assert_deadline_near(deadline: Option<time::Instant>, expected: time::Instant) {
+    let deadline = deadline.expect("deadline should be set");
+    let diff = if deadline > expected { deadline - expected } else { expected - deadline };
+    assert!(diff < time::Duration::from_millis(100), "deadline is {:?} away from expected", diff);
+}
+
+#[tokio::test]
+async fn spawn_deadline_parent_none_child_none() {
+    let timer = Timer::background();
+    let child = timer.spawn().await;
+
+    assert!(child.deadline().await.is_none());
+    assert!(child.spawn().await.deadline().await.is_none());
+}
+
+#[tokio::test]
+async fn spawn_deadline_parent_none_child_timeout() {
+    let now = time::Instant::now();
+    let timer = Timer::background();
+    let child = timer.spawn_with_timeout(time::Duration::from_secs(1)).await;
+
+    assert!(timer.deadline().await.is_none());
+    assert_deadline_near(child.deadline().await, now + time::Duration::from_secs(1));
+
+    let child = timer.spawn_in_seconds(3).await;
+    assert_deadline_near(child.deadline().await, now + time::Duration::from_secs(3));
+
+    let child = Timer::todo().spawn_in_milliseconds(500).await;
+    assert_deadline_near(child.deadline().await, now + time::Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn spawn_deadline_parent_none_grandchild_timeout() {
+    let now = time::Instant::now();
+    let child = Timer::background().spawn().await;
+    let grandchild = child.spawn_with_timeout(time::Duration::from_secs(2)).await;
+
+    assert!(child.deadline().await.is_none());
+    assert_deadline_near(grandchild.deadline().await, now + time::Duration::from_secs(2));
+    assert_deadline_near(grandchild.spawn().await.deadline().await, now + time::Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn spawn_deadline_parent_timeout_child_none() {
+    let timer = Timer::with_timeout(time::Duration::from_secs(5));
+    let child = timer.spawn().await;
+
+    assert_eq!(child.deadline().await, timer.deadline().await);
+}
+
+#[tokio::test]
+async fn spawn_deadline_parent_shorter() {
+    let timer = Timer::with_timeout(time::Duration::from_secs(1));
+    let child = timer.spawn_with_timeout(time::Duration::from_secs(10)).await;
+
+    assert_eq!(child.deadline().await, timer.deadline().await);
+}
+
+#[tokio::test]
+async fn spawn_deadline_parent_longer() {
+    let now = time::Instant::now();
+    let timer = Timer::with_timeout(time::Duration::from_secs(10));
+    let child = timer.spawn_with_timeout(time::Duration::from_secs(1)).await;
+
+    assert!(child.deadline().await < timer.deadline().await);
+    assert_deadline_near(child.deadline().await, now + time::Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn spawn_deadline_parent_expired() {
+    let timer = Timer::with_timeout(time::Duration::ZERO);
+    let child = timer.spawn_with_timeout(time::Duration::from_secs(10)).await;
+
+    assert_eq!(child.deadline().await, timer.deadline().await);
+    assert!(child.is_timeout().await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn spawn_deadline_background_child_times_out() {
+    let child = Timer::background().spawn_with_timeout(time::Duration::from_millis(100)).await;
+    let err = child.handle(tokio::time::sleep(time::Duration::from_secs(10))).await
+        .err().unwrap();
+
+    assert_eq!(err, Error::ContextTimeout);
+    assert!(child.is_timeout().await);
+}
+
+#[tokio::test]
+async fn dropped_child_detached() {
+    struct Payload;
+
+    impl context_async::Key for Payload {
+        type Value = Arc<()>;
+    }
+
+    let payload = Arc::new(());
+    let timer = Timer::background();
+
+    for _ in 0..100 {
+        let child = timer.with_value::<Payload>(payload.clone()).await;
+        let _ = child.spawn().await;
+        child.handle(async {}).await.unwrap();
+    }
+
+    // the parent doesn't keep any dropped child alive.
+    assert_eq!(Arc::strong_count(&payload), 1);
+
+    let child = timer.with_value::<Payload>(payload.clone()).await;
+    assert_eq!(Arc::strong_count(&payload), 2);
+    drop(child);
+    assert_eq!(Arc::strong_count(&payload), 1);
+}
+
+#[tokio::test]
+async fn child_keeps_parent_alive() {
+    let timer = Timer::background();
+    let grandchild = timer.spawn().await.spawn().await;
+
+    // the intermediate child is dropped, but still links `timer` to `grandchild`.
+    timer.cancel().await;
+    assert!(grandchild.is_cancelled().await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn spawn_from_cancelled() {
+    let timer = Timer::background();
+    timer.cancel().await;
+
+    let child = timer.spawn().await;
+    assert!(child.is_cancelled().await);
+    assert_eq!(child.error().await, Some(Error::ContextCancelled));
+
+    let child = timer.spawn_in_seconds(10).await;
+    assert!(child.is_cancelled().await);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn cancel_concurrent_fan_out() {
+    let timer = Timer::background();
+
+    let tasks: Vec<_> = (0..64).map(|_| {
+        let timer = timer.clone();
+        tokio::spawn(async move {
+            let child = timer.spawn().await.spawn().await;
+            child.handle(tokio::time::sleep(time::Duration::from_secs(10))).await
+        })
+    }).collect();
+
+    timer.cancel().await;
+
+    for task in tasks {
+        assert_eq!(task.await.unwrap().err(), Some(Error::ContextCancelled));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn spawn_drop_high_fan_out() {
+    let timer = Timer::background();
+
+    // siblings spawn and drop concurrently, each drop detaches only its own child.
+    let tasks: Vec<_> = (0..8).map(|_| {
+        let timer = timer.clone();
+        tokio::spawn(async move {
+            let mut kept = Vec::new();
+            for i in 0..10_000 {
+                let child = timer.spawn().await;
+                if i % 2 == 0 {
+                    kept.push(child);
+                }
+            }
+            kept
+        })
+    }).collect();
+
+    let mut childs = Vec::new();
+    for task in tasks {
+        childs.extend(task.await.unwrap());
+    }
+    assert_eq!(timer.snapshot().childs.len(), 40_000);
+
+    timer.cancel().await;
+    assert!(childs.iter().all(|child| child.try_is_cancelled()));
+
+    drop(childs);
+    assert!(timer.snapshot().childs.is_empty());
+}
+
+#[tokio::test]
+async fn sync_queries() {
+    let timer = Timer::with_timeout(time::Duration::from_millis(200));
+    let child = timer.spawn().await;
+
+    assert_eq!(timer.try_deadline(), timer.deadline().await);
+    assert!(!child.try_is_cancelled());
+    assert!(!child.try_is_timeout());
+    assert!(child.try_error().is_none());
+    assert!(!child.is_done());
+
+    let c = child.clone();
+    let observed = tokio::task::spawn_blocking(move || {
+        while !c.is_done() {
+            std::thread::sleep(time::Duration::from_millis(10));
+        }
+        c.try_error()
+    }).await.unwrap();
+
+    assert_eq!(observed, Some(Error::ContextTimeout));
+    assert!(child.try_is_timeout());
+    assert_eq!(child.try_error(), child.error().await);
+
+    let timer = Timer::background();
+    let child = timer.spawn().await;
+    timer.cancel().await;
+    assert!(child.try_is_cancelled());
+    assert!(child.is_done());
+    assert_eq!(child.try_error(), Some(Error::ContextCancelled));
+    assert_eq!(child.try_is_cancelled(), child.is_cancelled().await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn done_on_cancel() {
+    let timer = Timer::background();
+    let child = timer.spawn().await;
+    let done = child.done();
+
+    let waiter = tokio::spawn(done);
+    tokio::time::sleep(time::Duration::from_millis(50)).await;
+    assert!(!waiter.is_finished());
+
+    timer.cancel().await;
+    assert_eq!(waiter.await.unwrap(), Error::ContextCancelled);
+
+    // already cancelled.
+    assert_eq!(child.done().await, Error::ContextCancelled);
+}
+
+#[tokio::test(start_paused = true)]
+async fn done_on_timeout() {
+    let tc = TimeChecker::new();
+    let timer = Timer::in_milliseconds(500);
+
+    let err = tokio::select! {
+        _ = tokio::time::sleep(time::Duration::from_secs(10)) => None,
+        err = timer.done() => Some(err),
+    };
+
+    assert_eq!(err, Some(Error::ContextTimeout));
+    assert!(tc.not_exceed(time::Duration::from_millis(800)));
+    assert_eq!(timer.done().await, Error::ContextTimeout);
+}
+
+#[tokio::test(start_paused = true)]
+async fn done_polled_after_ready() {
+    let timer = Timer::background();
+    let mut done = timer.done();
+    timer.cancel().await;
+
+    // a resolved `Done` keeps resolving with the same error.
+    for _ in 0..3 {
+        let err = tokio::select! {
+            err = &mut done => err,
+            _ = tokio::time::sleep(time::Duration::from_secs(1)) => unreachable!(),
+        };
+        assert_eq!(err, Error::ContextCancelled);
+    }
+
+    let timer = Timer::in_milliseconds(100);
+    let mut done = timer.done();
+    for _ in 0..3 {
+        let err = tokio::select! {
+            err = &mut done => err,
+            _ = tokio::time::sleep(time::Duration::from_secs(1)) => unreachable!(),
+        };
+        assert_eq!(err, Error::ContextTimeout);
+    }
+}
+
+#[test]
+fn done_outside_runtime() {
+    let timer = Timer::in_seconds(5);
+    let done = timer.done();
+
+    // the deadline is only watched once `done` is polled in a runtime.
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_time().start_paused(true).build().unwrap();
+    assert_eq!(runtime.block_on(done), Error::ContextTimeout);
+}
+
+#[tokio::test(start_paused = true)]
+async fn done_outlives_context() {
+    let timer = Timer::in_milliseconds(100);
+    let done = timer.spawn().await.done();
+
+    // the context is only held by `done`.
+    assert_eq!(done.await, Error::ContextTimeout);
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancel_guard_on_drop() {
+    let timer = Timer::background();
+    let (child, guard) = timer.spawn_scoped().await;
+    let grandchild = child.spawn().await;
+    assert!(!guard.timer().is_cancelled().await);
+
+    let t = grandchild.clone();
+    let task = tokio::spawn(async move {
+        t.handle(tokio::time::sleep(time::Duration::from_secs(10))).await
+    });
+
+    drop(guard);
+    assert!(!timer.is_cancelled().await);
+    assert!(child.is_cancelled().await);
+    assert!(grandchild.is_cancelled().await);
+    assert_eq!(task.await.unwrap(), Err(Error::ContextCancelled));
+
+    {
+        let _guard = timer.cancel_guard();
+    }
+    assert!(timer.is_cancelled().await);
+}
+
+#[tokio::test]
+async fn cancel_guard_disarm() {
+    let timer = Timer::background();
+    let (child, guard) = timer.spawn_scoped().await;
+
+    let disarmed = guard.disarm();
+    assert!(!child.is_cancelled().await);
+
+    disarmed.cancel().await;
+    assert!(child.is_cancelled().await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn deadline_lazy_by_default() {
+    let timer = Timer::in_seconds(1);
+
+    tokio::time::sleep(time::Duration::from_secs(2)).await;
+    assert!(timer.is_timeout().await);
+    assert!(!timer.is_cancelled().await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn fire_on_deadline() {
+    let timer = Timer::in_seconds(5).fire_on_deadline();
+    let child = timer.spawn().await;
+    let grandchild = child.spawn_in_seconds(1).await;
+    let sibling = child.spawn().await;
+
+    let t = child.clone();
+    let waiter = tokio::spawn(async move { t.done().await });
+
+    tokio::time::sleep(time::Duration::from_millis(1500)).await;
+    assert!(grandchild.is_cancelled().await);
+    assert_eq!(grandchild.error().await, Some(Error::ContextTimeout));
+    assert!(!child.is_cancelled().await);
+    assert!(!sibling.is_cancelled().await);
+
+    tokio::time::sleep(time::Duration::from_secs(4)).await;
+    assert!(timer.try_is_cancelled());
+    assert!(child.try_is_cancelled());
+    assert!(sibling.try_is_cancelled());
+    assert_eq!(sibling.try_error(), Some(Error::ContextTimeout));
+    assert_eq!(waiter.await.unwrap(), Error::ContextTimeout);
+}
+
+#[tokio::test(start_paused = true)]
+async fn fire_on_deadline_after_cancel() {
+    let timer = Timer::in_seconds(1).fire_on_deadline();
+    let child = timer.spawn_in_seconds(10).await;
+    timer.cancel().await;
+
+    tokio::time::sleep(time::Duration::from_secs(2)).await;
+    assert_eq!(timer.error().await, Some(Error::ContextCancelled));
+    assert_eq!(child.error().await, Some(Error::ContextCancelled));
+
+    let timer = Timer::in_seconds(1);
+    timer.cancel().await;
+    let timer = timer.fire_on_deadline();
+
+    tokio::time::sleep(time::Duration::from_secs(2)).await;
+    assert_eq!(timer.error().await, Some(Error::ContextCancelled));
+}
+
+#[tokio::test(start_paused = true)]
+async fn fire_on_deadline_background() {
+    let timer = Timer::background().fire_on_deadline();
+    let child = timer.spawn_in_seconds(1).await;
+    let value = child.with_value::<Marker>(()).await;
+
+    tokio::time::sleep(time::Duration::from_secs(2)).await;
+    assert!(!timer.is_cancelled().await);
+    assert!(child.is_cancelled().await);
+    assert!(value.is_cancelled().await);
+}
+
+struct Marker;
+
+impl context_async::Key for Marker {
+    type Value = ();
+}
@@ -0,0 +1,221 @@
+#![cfg(feature = "tonic")]
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time;
+use bytes::{Buf, BufMut};
+use http::uri::PathAndQuery;
+use hyper_util::rt::TokioIo;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::server::{NamedService, UnaryService};
+use tonic::service::Interceptor;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::{Channel, Endpoint, Server};
+use tonic::{Code, Request, Response, Status};
+use context_async::{set_grpc_timeout, Context, GrpcTimeoutInterceptor, Timer};
+
+/// A codec of plain strings, so that no protobuf code generation is needed.
+#[derive(Debug, Clone, Default)]
+struct StringCodec;
+
+impl Codec for StringCodec {
+    type Encode = String;
+    type Decode = String;
+    type Encoder = Self;
+    type Decoder = Self;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        Self
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        Self
+    }
+}
+
+impl Encoder for StringCodec {
+    type Item = String;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put_slice(item.as_bytes());
+        Ok(())
+    }
+}
+
+impl Decoder for StringCodec {
+    type Item = String;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        let bytes = src.copy_to_bytes(src.remaining());
+        String::from_utf8(bytes.to_vec())
+            .map(Some)
+            .map_err(|err| Status::internal(err.to_string()))
+    }
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// The handler of a method, which receives the context rebuilt from the request.
+#[derive(Clone)]
+struct Method(fn(Timer) -> BoxFuture<Result<String, Status>>);
+
+impl UnaryService<String> for Method {
+    type Response = String;
+    type Future = BoxFuture<Result<Response<String>, Status>>;
+
+    fn call(&mut self, request: Request<String>) -> Self::Future {
+        let ctx = Timer::from_grpc_request(&request);
+        let fut = (self.0)(ctx);
+        Box::pin(async move { fut.await.map(Response::new) })
+    }
+}
+
+#[derive(Clone)]
+struct Echo;
+
+impl NamedService for Echo {
+    const NAME: &'static str = "test.Echo";
+}
+
+impl tower::Service<http::Request<tonic::body::Body>> for Echo {
+    type Response = http::Response<tonic::body::Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<tonic::body::Body>) -> Self::Future {
+        let method = match req.uri().path() {
+            // return the remaining time of the context, in milliseconds.
+            "/test.Echo/Remaining" => Method(|ctx| Box::pin(async move {
+                Ok(match ctx.deadline().await {
+                    Some(deadline) => deadline.saturating_duration_since(time::Instant::now()).as_millis().to_string(),
+                    None => String::from("none"),
+                })
+            })),
+            // a slow call with a shorter deadline than the client's.
+            "/test.Echo/Slow" => Method(|ctx| Box::pin(async move {
+                let ctx = ctx.spawn_in_milliseconds(100).await;
+                ctx.handle(tokio::time::sleep(time::Duration::from_secs(10))).await?;
+                Ok(String::new())
+            })),
+            // a call cancelled on the server side.
+            "/test.Echo/Cancel" => Method(|ctx| Box::pin(async move {
+                ctx.cancel().await;
+                ctx.handle(async { String::new() }).await
+                    .map_err(Status::from)
+            })),
+            _ => return Box::pin(async { Ok(Status::unimplemented("").into_http()) }),
+        };
+
+        Box::pin(async move {
+            Ok(tonic::server::Grpc::new(StringCodec).unary(method, req).await)
+        })
+    }
+}
+
+/// serve `Echo` over an in-process duplex transport.
+async fn channel() -> Channel {
+    let (client, server) = tokio::io::duplex(1024);
+
+    tokio::spawn(async move {
+        Server::builder()
+            .add_service(Echo)
+            .serve_with_incoming(futures::stream::iter(vec![Ok::<_, std::io::Error>(server)]))
+            .await
+            .unwrap();
+    });
+
+    let client = Arc::new(Mutex::new(Some(client)));
+    Endpoint::from_static("http://[::]:50051")
+        .connect_with_connector(tower::service_fn(move |_: http::Uri| {
+            let client = client.lock().unwrap().take();
+            async move {
+                client.map(TokioIo::new)
+                    .ok_or_else(|| std::io::Error::other("the duplex is already used"))
+            }
+        }))
+        .await
+        .unwrap()
+}
+
+async fn call(channel: Channel, ctx: &Timer, path: &'static str) -> Result<String, Status> {
+    let channel = InterceptedService::new(channel, GrpcTimeoutInterceptor::new(ctx));
+    let mut grpc = tonic::client::Grpc::new(channel);
+    grpc.ready().await.unwrap();
+
+    grpc.unary(Request::new(String::new()), PathAndQuery::from_static(path), StringCodec).await
+        .map(Response::into_inner)
+}
+
+#[tokio::test]
+async fn grpc_deadline_propagated() {
+    let channel = channel().await;
+
+    let ctx = Timer::in_seconds(2);
+    let remaining: u128 = call(channel.clone(), &ctx, "/test.Echo/Remaining").await.unwrap().parse().unwrap();
+    assert!(remaining > 1500 && remaining <= 2000);
+
+    let ctx = Timer::background();
+    let remaining = call(channel, &ctx, "/test.Echo/Remaining").await.unwrap();
+    assert_eq!(remaining, "none");
+}
+
+#[tokio::test]
+async fn grpc_status_mapping() {
+    let channel = channel().await;
+    let ctx = Timer::in_seconds(5);
+
+    let status = call(channel.clone(), &ctx, "/test.Echo/Slow").await.err().unwrap();
+    assert_eq!(status.code(), Code::DeadlineExceeded);
+
+    let status = call(channel, &ctx, "/test.Echo/Cancel").await.err().unwrap();
+    assert_eq!(status.code(), Code::Cancelled);
+}
+
+#[tokio::test]
+async fn grpc_client_context_done() {
+    let ctx = Timer::background();
+    ctx.cancel().await;
+
+    let status = GrpcTimeoutInterceptor::new(&ctx).call(Request::new(())).err().unwrap();
+    assert_eq!(status.code(), Code::Cancelled);
+
+    let ctx = Timer::with_timeout(time::Duration::ZERO);
+    tokio::time::sleep(time::Duration::from_millis(10)).await;
+    let status = GrpcTimeoutInterceptor::new(&ctx).call(Request::new(())).err().unwrap();
+    assert_eq!(status.code(), Code::DeadlineExceeded);
+}
+
+#[tokio::test]
+async fn grpc_timeout_header() {
+    let ctx = Timer::in_milliseconds(1500);
+    let mut request = Request::new(());
+    set_grpc_timeout(&ctx, &mut request).unwrap();
+
+    let value = request.metadata().get("grpc-timeout").unwrap().to_str().unwrap();
+    assert!(value.ends_with('u'), "{}", value);
+
+    // rebuilt on the server side.
+    let server = Timer::from_grpc_request(&request);
+    let remaining = server.deadline().await.unwrap().saturating_duration_since(time::Instant::now());
+    assert!(remaining > time::Duration::from_millis(1000) && remaining <= time::Duration::from_millis(1500));
+
+    // a shorter existing timeout is kept.
+    let mut request = Request::new(());
+    request.metadata_mut().insert("grpc-timeout", "10m".parse().unwrap());
+    set_grpc_timeout(&ctx, &mut request).unwrap();
+    assert_eq!(request.metadata().get("grpc-timeout").unwrap(), "10m");
+
+    // a malformed header is ignored.
+    let mut request = Request::new(());
+    request.metadata_mut().insert("grpc-timeout", "123456789S".parse().unwrap());
+    assert!(Timer::from_grpc_request(&request).deadline().await.is_none());
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "tower")]
+
+use std::convert::Infallible;
+use std::time;
+use tower::{Layer, ServiceExt};
+use context_async::{Context, ContextLayer, Error, TimeChecker, Timer};
+
+async fn sleep_and_report(req: http::Request<time::Duration>) -> Result<Option<time::Duration>, Infallible> {
+    let ctx = req.extensions().get::<Timer>().unwrap().clone();
+    tokio::time::sleep(*req.body()).await;
+
+    Ok(ctx.deadline().await.map(|deadline| deadline.saturating_duration_since(time::Instant::now())))
+}
+
+#[tokio::test]
+async fn layer_attaches_timer() {
+    let service = ContextLayer::new()
+        .timeout(time::Duration::from_secs(5))
+        .layer(tower::service_fn(sleep_and_report));
+
+    let remaining = service.oneshot(http::Request::new(time::Duration::ZERO)).await.unwrap().unwrap();
+    assert!(remaining > time::Duration::from_millis(4500));
+
+    let service = ContextLayer::new().layer(tower::service_fn(sleep_and_report));
+    let remaining = service.oneshot(http::Request::new(time::Duration::ZERO)).await.unwrap();
+    assert!(remaining.is_none());
+}
+
+#[tokio::test]
+async fn layer_timeout() {
+    let tc = TimeChecker::new();
+    let service = ContextLayer::new()
+        .timeout(time::Duration::from_millis(200))
+        .layer(tower::service_fn(sleep_and_report));
+
+    let err = service.oneshot(http::Request::new(time::Duration::from_secs(10))).await.err().unwrap();
+    assert_eq!(err.downcast_ref::<Error>(), Some(&Error::ContextTimeout));
+    assert!(tc.not_exceed(time::Duration::from_millis(500)));
+}
+
+#[tokio::test]
+async fn layer_timeout_header() {
+    let service = ContextLayer::new()
+        .timeout(time::Duration::from_secs(10))
+        .header(http::HeaderName::from_static("grpc-timeout"))
+        .layer(tower::service_fn(sleep_and_report));
+
+    let req = http::Request::builder()
+        .header("grpc-timeout", "100m")
+        .body(time::Duration::from_secs(10))
+        .unwrap();
+
+    let err = service.oneshot(req).await.err().unwrap();
+    assert_eq!(err.downcast_ref::<Error>(), Some(&Error::ContextTimeout));
+
+    // the default header.
+    let service = ContextLayer::new().layer(tower::service_fn(sleep_and_report));
+    let req = http::Request::builder()
+        .header("x-request-timeout", "2000")
+        .body(time::Duration::ZERO)
+        .unwrap();
+
+    let remaining = service.oneshot(req).await.unwrap().unwrap();
+    assert!(remaining > time::Duration::from_millis(1500) && remaining <= time::Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn layer_root_cancelled() {
+    let root = Timer::background();
+    let service = ContextLayer::new()
+        .root(root.clone())
+        .layer(tower::service_fn(sleep_and_report));
+
+    let requests: Vec<_> = (0..4).map(|_| {
+        let service = service.clone();
+        tokio::spawn(service.oneshot(http::Request::new(time::Duration::from_secs(10))))
+    }).collect();
+
+    tokio::time::sleep(time::Duration::from_millis(50)).await;
+    root.cancel().await;
+
+    for request in requests {
+        let err = request.await.unwrap().err().unwrap();
+        assert_eq!(err.downcast_ref::<Error>(), Some(&Error::ContextCancelled));
+    }
+}
+
+#[tokio::test]
+async fn layer_keeps_context_after_response() {
+    let service = ContextLayer::new().layer(tower::service_fn(|req: http::Request<()>| async move {
+        Ok::<_, Infallible>(req.extensions().get::<Timer>().unwrap().clone())
+    }));
+
+    // a streaming body may still use the context.
+    let ctx = service.oneshot(http::Request::new(())).await.unwrap();
+    assert!(!ctx.is_cancelled().await);
+}
+
+#[tokio::test]
+async fn layer_cancels_when_dropped() {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let service = ContextLayer::new().layer(tower::service_fn(move |req: http::Request<()>| {
+        let ctx = req.extensions().get::<Timer>().unwrap().clone();
+        sender.send(ctx).unwrap();
+        std::future::pending::<Result<(), Infallible>>()
+    }));
+
+    let fut = service.oneshot(http::Request::new(()));
+    tokio::select! {
+        _ = fut => unreachable!(),
+        _ = tokio::time::sleep(time::Duration::from_millis(10)) => {},
+    }
+
+    let ctx = receiver.recv().await.unwrap();
+    assert!(ctx.is_cancelled().await);
+}
@@ -0,0 +1,185 @@
+#![cfg(feature = "tracing")]
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+use context_async::{Context, Timer};
+
+#[derive(Debug, Default)]
+struct SpanRecord {
+    name: &'static str,
+    parent: Option<u64>,
+    fields: HashMap<&'static str, String>,
+}
+
+#[derive(Default)]
+struct Recorded {
+    spans: HashMap<u64, SpanRecord>,
+    events: Vec<(String, Option<u64>)>,
+}
+
+/// A layer which records the spans and the events, to check them in the tests.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Recorded>>);
+
+struct Fields<'a>(&'a mut HashMap<&'static str, String>);
+
+impl Visit for Fields<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Recorder {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: LayerContext<'_, S>) {
+        let mut record = SpanRecord {
+            name: attrs.metadata().name(),
+            parent: ctx.span(id).and_then(|span| span.parent()).map(|parent| parent.id().into_u64()),
+            ..Default::default()
+        };
+        attrs.record(&mut Fields(&mut record.fields));
+
+        self.0.lock().unwrap().spans.insert(id.into_u64(), record);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _: LayerContext<'_, S>) {
+        if let Some(record) = self.0.lock().unwrap().spans.get_mut(&id.into_u64()) {
+            values.record(&mut Fields(&mut record.fields));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: LayerContext<'_, S>) {
+        let mut fields = HashMap::new();
+        event.record(&mut Fields(&mut fields));
+
+        if let Some(message) = fields.remove("message") {
+            let span = ctx.event_span(event).map(|span| span.id().into_u64());
+            self.0.lock().unwrap().events.push((message, span));
+        }
+    }
+}
+
+impl Recorder {
+    fn install(&self) -> tracing::subscriber::DefaultGuard {
+        tracing::subscriber::set_default(Registry::default().with(self.clone()))
+    }
+
+    fn parent(&self, span: &tracing::Span) -> Option<u64> {
+        self.0.lock().unwrap().spans[&id(span)].parent
+    }
+
+    fn field(&self, span: u64, field: &str) -> Option<String> {
+        self.0.lock().unwrap().spans[&span].fields.get(field).cloned()
+    }
+}
+
+fn id(span: &tracing::Span) -> u64 {
+    span.id().expect("span should be enabled").into_u64()
+}
+
+#[tokio::test]
+async fn span_hierarchy() {
+    let recorder = Recorder::default();
+    let _guard = recorder.install();
+
+    let request = tracing::info_span!("request");
+    let root = request.in_scope(Timer::background);
+    let child = root.spawn().await;
+    let grandchild = child.spawn_in_seconds(5).await;
+    let value = grandchild.with_value::<Marker>(()).await;
+
+    assert_eq!(recorder.0.lock().unwrap().spans[&id(root.span())].name, "context");
+    assert_eq!(recorder.parent(root.span()), Some(id(&request)));
+    assert_eq!(recorder.parent(child.span()), Some(id(root.span())));
+    assert_eq!(recorder.parent(grandchild.span()), Some(id(child.span())));
+    assert_eq!(recorder.parent(value.span()), Some(id(grandchild.span())));
+
+    assert!(recorder.field(id(child.span()), "deadline").is_none());
+    assert!(recorder.field(id(grandchild.span()), "deadline").is_some());
+}
+
+#[cfg(feature = "name")]
+#[tokio::test]
+async fn span_name() {
+    let recorder = Recorder::default();
+    let _guard = recorder.install();
+
+    let root = Timer::named("req");
+    let child = root.spawn_named("db").await;
+
+    assert_eq!(recorder.field(id(child.span()), "name"), Some(child.name().await.to_string()));
+}
+
+#[tokio::test(start_paused = true)]
+async fn span_outcome() {
+    let recorder = Recorder::default();
+    let _guard = recorder.install();
+
+    let completed = Timer::background();
+    let cancelled = completed.spawn().await;
+    let timeout = Timer::in_seconds(1);
+    let fired = Timer::in_seconds(1).fire_on_deadline();
+    let finished = Timer::in_seconds(1);
+    let ids = [completed.span(), cancelled.span(), timeout.span(), fired.span(), finished.span()].map(id);
+
+    finished.handle(async {}).await.unwrap();
+    cancelled.cancel().await;
+    tokio::time::sleep(time::Duration::from_secs(2)).await;
+    assert_eq!(recorder.field(ids[1], "outcome").as_deref(), Some("cancelled"));
+    assert_eq!(recorder.field(ids[3], "outcome").as_deref(), Some("timeout"));
+    assert_eq!(recorder.field(ids[2], "outcome"), None);
+
+    // the timeout is recorded when it is observed.
+    assert!(timeout.is_timeout().await);
+    assert_eq!(recorder.field(ids[2], "outcome").as_deref(), Some("timeout"));
+
+    // finished in time, and dropped after its deadline.
+    drop((cancelled, completed, timeout, fired, finished));
+    assert_eq!(recorder.field(ids[0], "outcome").as_deref(), Some("completed"));
+    assert_eq!(recorder.field(ids[1], "outcome").as_deref(), Some("cancelled"));
+    assert_eq!(recorder.field(ids[2], "outcome").as_deref(), Some("timeout"));
+    assert_eq!(recorder.field(ids[4], "outcome").as_deref(), Some("completed"));
+}
+
+#[tokio::test]
+async fn span_instruments_handle() {
+    let recorder = Recorder::default();
+    let _guard = recorder.install();
+
+    let ctx = Timer::background().spawn().await;
+    ctx.handle(async {
+        tracing::info!("inside");
+    }).await.unwrap();
+
+    let task = ctx.spawn_task(async {
+        tracing::info!("task");
+    }).await;
+    let task_span = id(task.timer().span());
+    task.await.unwrap();
+
+    tracing::info!("outside");
+
+    let events = recorder.0.lock().unwrap().events.clone();
+    assert_eq!(events, [
+        (String::from("inside"), Some(id(ctx.span()))),
+        (String::from("task"), Some(task_span)),
+        (String::from("outside"), None),
+    ]);
+}
+
+struct Marker;
+
+impl context_async::Key for Marker {
+    type Value = ();
+}
@@ -0,0 +1,59 @@
+use context_async::{Context, Key, Timer};
+
+struct RequestId;
+
+impl Key for RequestId {
+    type Value = u64;
+}
+
+struct User;
+
+impl Key for User {
+    type Value = String;
+}
+
+#[tokio::test]
+async fn value_missing() {
+    let timer = Timer::background();
+    assert!(timer.value::<RequestId>().await.is_none());
+
+    let child = timer.spawn().await;
+    assert!(child.value::<RequestId>().await.is_none());
+}
+
+#[tokio::test]
+async fn value_inherited() {
+    let root = Timer::background();
+    let ctx = root.with_value::<RequestId>(42).await;
+
+    assert!(root.value::<RequestId>().await.is_none());
+    assert_eq!(ctx.value::<RequestId>().await.as_deref(), Some(&42));
+
+    let child = ctx.spawn().await;
+    let child_of_child = child.spawn_in_seconds(10).await;
+    assert_eq!(child.value::<RequestId>().await.as_deref(), Some(&42));
+    assert_eq!(child_of_child.value::<RequestId>().await.as_deref(), Some(&42));
+
+    let ctx = child_of_child.with_value::<User>(String::from("jack")).await;
+    assert_eq!(ctx.value::<RequestId>().await.as_deref(), Some(&42));
+    assert_eq!(ctx.value::<User>().await.as_deref().map(String::as_str), Some("jack"));
+    assert!(child_of_child.value::<User>().await.is_none());
+}
+
+#[tokio::test]
+async fn value_shadowed() {
+    let ctx = Timer::background().with_value::<RequestId>(1).await;
+    let child = ctx.with_value::<RequestId>(2).await;
+
+    assert_eq!(ctx.value::<RequestId>().await.as_deref(), Some(&1));
+    assert_eq!(child.value::<RequestId>().await.as_deref(), Some(&2));
+}
+
+#[tokio::test]
+async fn value_cancel_with_parent() {
+    let root = Timer::background();
+    let ctx = root.with_value::<RequestId>(42).await;
+
+    root.cancel().await;
+    assert!(ctx.is_cancelled().await);
+}
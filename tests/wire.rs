@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::time::{self, SystemTime, UNIX_EPOCH};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use context_async::{wire, Baggage, Context, Error, TimeChecker, Timer, WireContext};
+
+fn random_string(rng: &mut StdRng) -> String {
+    let len = rng.gen_range(0..12);
+    (0..len)
+        .map(|_| match rng.gen_range(0..4) {
+            0 => rng.gen_range('a'..='z'),
+            1 => *b";,=%&\n ".get(rng.gen_range(0..7)).unwrap() as char,
+            2 => rng.gen_range('\u{80}'..='\u{7ff}'),
+            _ => rng.gen_range('0'..='9'),
+        })
+        .collect()
+}
+
+fn random_wire(rng: &mut StdRng) -> WireContext {
+    WireContext {
+        timeout: rng.gen_bool(0.5).then(|| time::Duration::from_millis(rng.gen())),
+        deadline: rng.gen_bool(0.5)
+            .then(|| UNIX_EPOCH + time::Duration::from_millis(rng.gen_range(0..1 << 48))),
+        name: rng.gen_bool(0.5).then(|| random_string(rng)),
+        cancelled: rng.gen(),
+        baggage: (0..rng.gen_range(0..4))
+            .map(|_| (random_string(rng), random_string(rng)))
+            .collect(),
+    }
+}
+
+#[test]
+fn wire_round_trip() {
+    let mut rng = StdRng::seed_from_u64(18);
+
+    for _ in 0..1000 {
+        let wire = random_wire(&mut rng);
+
+        assert_eq!(WireContext::from_bytes(&wire.to_bytes()), Ok(wire.clone()));
+        assert_eq!(wire.to_string().parse(), Ok(wire.clone()));
+
+        let headers = wire.to_headers();
+        let headers = headers.iter().map(|(name, value)| (*name, value.as_str()));
+        assert_eq!(WireContext::from_headers(headers), Ok(wire));
+    }
+}
+
+#[test]
+fn wire_string_format() {
+    let wire = WireContext {
+        timeout: Some(time::Duration::from_millis(1500)),
+        name: Some(String::from("a b")),
+        cancelled: true,
+        baggage: BTreeMap::from([(String::from("k"), String::from("v;1"))]),
+        ..Default::default()
+    };
+
+    assert_eq!(wire.to_string(), "v1;timeout=1500;name=a%20b;cancelled;baggage=k=v%3B1");
+    assert_eq!("v1;unknown=1;timeout=1500".parse::<WireContext>().unwrap().timeout, wire.timeout);
+}
+
+#[test]
+fn wire_malformed() {
+    assert!("".parse::<WireContext>().is_err());
+    assert!("v2;timeout=1".parse::<WireContext>().is_err());
+    assert!("v1;timeout=-1".parse::<WireContext>().is_err());
+    assert!("v1;timeout=1;timeout=2".parse::<WireContext>().is_err());
+    assert!("v1;name=%zz".parse::<WireContext>().is_err());
+    assert!("v1;cancelled=1".parse::<WireContext>().is_err());
+
+    assert!(WireContext::from_bytes(&[]).is_err());
+    assert!(WireContext::from_bytes(&[2, 0]).is_err());
+    assert!(WireContext::from_bytes(&[1, 0x80]).is_err());
+    assert!(WireContext::from_bytes(&[1, 0, 0]).is_err());
+    assert!(WireContext::from_bytes(&[1, 4, 0xff, 0xff, 0xff, 0xff, 0x0f]).is_err());
+    assert!(WireContext::from_bytes(&[1, 1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]).is_err());
+
+    assert!(matches!(WireContext::from_headers([(wire::TIMEOUT_HEADER, "abc")]), Err(wire::DecodeError { .. })));
+    assert!(WireContext::from_headers([("x-context-baggage", "novalue")]).is_err());
+    assert!(WireContext::from_headers([("x-context-name", "%+1")]).is_err());
+    assert!(WireContext::from_headers([("x-context-baggage", "a=%-1")]).is_err());
+    assert!(WireContext::from_headers([("x-context-cancelled", "1"), ("X-Context-Cancelled", "1")]).is_err());
+    assert_eq!(WireContext::from_headers([("x-other", "1")]), Ok(WireContext::default()));
+}
+
+#[test]
+fn wire_fuzz_bytes() {
+    let mut rng = StdRng::seed_from_u64(0x18);
+
+    for _ in 0..10000 {
+        let len = rng.gen_range(0..32);
+        let mut bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        if rng.gen_bool(0.5) && !bytes.is_empty() {
+            bytes[0] = 1;
+        }
+        let _ = WireContext::from_bytes(&bytes);
+
+        let mut bytes = random_wire(&mut rng).to_bytes();
+        for _ in 0..rng.gen_range(1..4) {
+            let i = rng.gen_range(0..bytes.len());
+            match rng.gen_range(0..3) {
+                0 => bytes[i] = rng.gen(),
+                1 => bytes.truncate(i),
+                _ => bytes.insert(i, rng.gen()),
+            }
+            if bytes.is_empty() {
+                break;
+            }
+        }
+        if let Ok(wire) = WireContext::from_bytes(&bytes) {
+            assert_eq!(WireContext::from_bytes(&wire.to_bytes()), Ok(wire));
+        }
+    }
+}
+
+#[test]
+fn wire_fuzz_strings() {
+    let mut rng = StdRng::seed_from_u64(0x18);
+
+    for _ in 0..10000 {
+        let _ = random_string(&mut rng).parse::<WireContext>();
+        let _ = format!("v1;{}", random_string(&mut rng)).parse::<WireContext>();
+
+        let mut chars: Vec<char> = random_wire(&mut rng).to_string().chars().collect();
+        for _ in 0..rng.gen_range(1..4) {
+            let i = rng.gen_range(0..chars.len());
+            match rng.gen_range(0..3) {
+                0 => chars[i] = *b"v1;=,%0aF".get(rng.gen_range(0..9)).unwrap() as char,
+                1 => { chars.remove(i); },
+                _ => chars.insert(i, rng.gen()),
+            }
+            if chars.is_empty() {
+                break;
+            }
+        }
+        let string: String = chars.into_iter().collect();
+        if let Ok(wire) = string.parse::<WireContext>() {
+            assert_eq!(wire.to_string().parse(), Ok(wire.clone()));
+        }
+
+        let name = random_string(&mut rng);
+        let value = random_string(&mut rng);
+        let _ = WireContext::from_headers([(name.as_str(), value.as_str())]);
+        for header in ["x-context-timeout", "x-context-deadline", "x-context-name", "x-context-cancelled", "x-context-baggage"] {
+            let _ = WireContext::from_headers([(header, value.as_str())]);
+        }
+    }
+}
+
+#[tokio::test]
+async fn wire_into_timer() {
+    let baggage = BTreeMap::from([(String::from("tenant"), String::from("acme"))]);
+    let ctx = Timer::in_seconds(5).with_value::<Baggage>(baggage.clone()).await;
+
+    let wire = WireContext::new(&ctx);
+    assert!(!wire.cancelled);
+    assert!(wire.timeout.unwrap() <= time::Duration::from_secs(5));
+    assert_eq!(wire.baggage, baggage);
+
+    let timer: Timer = wire.to_string().parse::<WireContext>().unwrap().into_timer();
+    let remaining = timer.deadline().await.unwrap() - time::Instant::now();
+    assert!(remaining <= time::Duration::from_secs(5));
+    assert!(remaining > time::Duration::from_secs(4));
+    assert_eq!(timer.value::<Baggage>().await.as_deref(), Some(&baggage));
+}
+
+#[tokio::test]
+async fn wire_into_timer_earlier_deadline() {
+    let wire = WireContext {
+        timeout: Some(time::Duration::from_secs(10)),
+        deadline: Some(SystemTime::now() + time::Duration::from_millis(100)),
+        ..Default::default()
+    };
+
+    let timer = wire.into_timer();
+    let checker = TimeChecker::new();
+    assert_eq!(timer.handle(std::future::pending::<()>()).await, Err(Error::ContextTimeout));
+    assert!(checker.not_exceed(time::Duration::from_millis(150)));
+}
+
+#[tokio::test]
+async fn wire_into_timer_passed_deadline() {
+    let wire = WireContext {
+        deadline: Some(UNIX_EPOCH),
+        ..Default::default()
+    };
+
+    assert!(wire.into_timer().is_timeout().await);
+}
+
+#[tokio::test]
+async fn wire_into_timer_cancelled() {
+    let ctx = Timer::background();
+    ctx.cancel().await;
+
+    let timer = WireContext::new(&ctx).into_timer();
+    assert!(timer.is_cancelled().await);
+    assert!(timer.deadline().await.is_none());
+}
+
+#[tokio::test]
+async fn wire_into_timer_overflow() {
+    let wire = WireContext {
+        timeout: Some(time::Duration::from_millis(u64::MAX)),
+        ..Default::default()
+    };
+
+    assert!(!wire.into_timer().is_timeout().await);
+}
+
+#[cfg(feature = "name")]
+#[tokio::test]
+async fn wire_name() {
+    let ctx = Timer::named("req").spawn_named("db").await;
+    let timer = WireContext::new(&ctx).into_timer();
+
+    assert_eq!(timer.name().await, ctx.name().await);
+    assert_eq!(timer.spawn().await.name().await.parent(), Some(&ctx.name().await));
+}
@@ -2,7 +2,7 @@ use std::sync::Arc;
 use tokio::time;
 use context_async::{Context, Error, TimeChecker, Timer, With};
 
-#[tokio::test]
+#[tokio::test(start_paused = true)]
 async fn with_simple() {
     let tc = TimeChecker::new();
 
@@ -49,7 +49,7 @@ impl Context for DataContext {
     }
 }
 
-#[tokio::test]
+#[tokio::test(start_paused = true)]
 async fn context_with_data() {
     let ctx = DataContext {
         timer: Timer::with_timeout(time::Duration::from_secs(4)),